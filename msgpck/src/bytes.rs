@@ -0,0 +1,184 @@
+//! Wrapper types and helpers for packing byte containers as msgpack bin.
+
+use core::ops::Deref;
+
+use crate::{ExpectedKind, Marker, MsgPack, MsgUnpack, Piece, UnpackCtx, UnpackErr};
+
+#[cfg(feature = "alloc")]
+use crate::{PackErr, Write};
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+#[cfg(feature = "alloc")]
+use core::ops::DerefMut;
+
+/// A byte slice which is packed as bin.
+///
+/// This is the same as packing a `&[u8]`, but makes the encoding explicit in field types.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Bytes<'a>(pub &'a [u8]);
+
+/// A `Vec<u8>` which is packed as bin, instead of as an array of integers.
+///
+/// It may be unpacked from either a bin or an array, for compatibility with data that was packed
+/// as a `Vec<u8>`. See also `#[msgpck(bytes)]`, which does the same for a field.
+///
+/// ```
+/// use msgpck::{pack_vec, ByteBuf};
+///
+/// let packed = pack_vec(&ByteBuf(vec![0xff; 3])).unwrap();
+/// assert_eq!(packed, [0xc4, 0x03, 0xff, 0xff, 0xff]);
+/// ```
+#[cfg(feature = "alloc")]
+#[derive(Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ByteBuf(pub Vec<u8>);
+
+/// A byte container which can be unpacked by [unpack_bytes].
+pub trait UnpackBytes<'buf>: Sized {
+    /// Create the value from the contents of a bin.
+    fn from_bin(bin: &'buf [u8]) -> Result<Self, UnpackErr>;
+
+    /// Unpack the value from a msgpack array of integers.
+    ///
+    /// The default implementation returns [UnpackErr::WrongMarker], for containers that can only
+    /// borrow the contents of a bin.
+    fn unpack_array(bytes: &mut &'buf [u8], _ctx: &mut UnpackCtx) -> Result<Self, UnpackErr> {
+        let &b = bytes.first().ok_or(UnpackErr::UnexpectedEof)?;
+        Err(UnpackErr::wrong_marker(
+            Marker::from_u8(b),
+            ExpectedKind::BIN,
+        ))
+    }
+}
+
+/// Helper function that unpacks a byte container from a bin, or from an array of integers if the
+/// container supports it.
+///
+/// This is used for fields marked with `#[msgpck(bytes)]`.
+pub fn unpack_bytes<'buf, T: UnpackBytes<'buf>>(
+    bytes: &mut &'buf [u8],
+    ctx: &mut UnpackCtx,
+) -> Result<T, UnpackErr> {
+    let &b = bytes.first().ok_or(UnpackErr::UnexpectedEof)?;
+
+    match Marker::from_u8(b) {
+        Marker::Bin8 | Marker::Bin16 | Marker::Bin32 => {
+            T::from_bin(MsgUnpack::unpack_with_ctx(bytes, ctx)?)
+        }
+        m if ExpectedKind::of(m) == ExpectedKind::ARRAY => T::unpack_array(bytes, ctx),
+        m => Err(UnpackErr::wrong_marker(
+            m,
+            ExpectedKind::BIN | ExpectedKind::ARRAY,
+        )),
+    }
+}
+
+impl<'buf> UnpackBytes<'buf> for &'buf [u8] {
+    fn from_bin(bin: &'buf [u8]) -> Result<Self, UnpackErr> {
+        Ok(bin)
+    }
+}
+
+impl<'buf, const N: usize> UnpackBytes<'buf> for [u8; N] {
+    fn from_bin(bin: &'buf [u8]) -> Result<Self, UnpackErr> {
+        bin.try_into().map_err(|_| UnpackErr::InvalidLength {
+            expected: N,
+            found: bin.len(),
+        })
+    }
+
+    fn unpack_array(bytes: &mut &'buf [u8], ctx: &mut UnpackCtx) -> Result<Self, UnpackErr> {
+        MsgUnpack::unpack_with_ctx(bytes, ctx)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'buf> UnpackBytes<'buf> for Vec<u8> {
+    fn from_bin(bin: &'buf [u8]) -> Result<Self, UnpackErr> {
+        Ok(bin.to_vec())
+    }
+
+    fn unpack_array(bytes: &mut &'buf [u8], ctx: &mut UnpackCtx) -> Result<Self, UnpackErr> {
+        MsgUnpack::unpack_with_ctx(bytes, ctx)
+    }
+}
+
+impl MsgPack for Bytes<'_> {
+    fn pack(&self) -> impl Iterator<Item = Piece<'_>> {
+        self.0.pack()
+    }
+}
+
+impl<'buf> MsgUnpack<'buf> for Bytes<'buf> {
+    fn unpack(bytes: &mut &'buf [u8]) -> Result<Self, UnpackErr>
+    where
+        Self: Sized,
+    {
+        Self::unpack_with_ctx(bytes, &mut UnpackCtx::new())
+    }
+
+    fn unpack_with_ctx(bytes: &mut &'buf [u8], ctx: &mut UnpackCtx) -> Result<Self, UnpackErr>
+    where
+        Self: Sized,
+    {
+        unpack_bytes(bytes, ctx).map(Bytes)
+    }
+}
+
+impl<'a> Deref for Bytes<'a> {
+    type Target = &'a [u8];
+
+    fn deref(&self) -> &&'a [u8] {
+        &self.0
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl MsgPack for ByteBuf {
+    fn pack(&self) -> impl Iterator<Item = Piece<'_>> {
+        self.0.as_slice().pack()
+    }
+
+    fn pack_with_writer(&self, w: &mut dyn Write) -> Result<usize, PackErr> {
+        self.0.as_slice().pack_with_writer(w)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'buf> MsgUnpack<'buf> for ByteBuf {
+    fn unpack(bytes: &mut &'buf [u8]) -> Result<Self, UnpackErr>
+    where
+        Self: Sized,
+    {
+        Self::unpack_with_ctx(bytes, &mut UnpackCtx::new())
+    }
+
+    fn unpack_with_ctx(bytes: &mut &'buf [u8], ctx: &mut UnpackCtx) -> Result<Self, UnpackErr>
+    where
+        Self: Sized,
+    {
+        unpack_bytes(bytes, ctx).map(ByteBuf)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl From<Vec<u8>> for ByteBuf {
+    fn from(bytes: Vec<u8>) -> Self {
+        ByteBuf(bytes)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl Deref for ByteBuf {
+    type Target = Vec<u8>;
+
+    fn deref(&self) -> &Vec<u8> {
+        &self.0
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl DerefMut for ByteBuf {
+    fn deref_mut(&mut self) -> &mut Vec<u8> {
+        &mut self.0
+    }
+}
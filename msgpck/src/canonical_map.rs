@@ -0,0 +1,97 @@
+use core::ops::{Deref, DerefMut};
+
+use crate::{util::pack_map_sorted, MsgPack, MsgUnpack, Piece, UnpackCtx, UnpackErr};
+
+/// A wrapper around a map which is packed deterministically.
+///
+/// The entries are sorted by the packed bytes of their keys, which means that equal maps are
+/// always packed to the same bytes, regardless of their iteration order.
+///
+/// [MsgPack] is implemented for any map that can be iterated over by reference, i.e. where `&M`
+/// implements `IntoIterator<Item = (&K, &V)>`. This includes maps without a defined order, e.g.
+/// `HashMap`, and maps which are sorted by something else than the packed bytes of their keys,
+/// e.g. a `BTreeMap` with negative integer keys.
+///
+/// Unpacking is done the same way as for the inner map.
+///
+/// ```
+/// # #[cfg(feature = "std")] {
+/// use std::collections::HashMap;
+/// use msgpck::{pack_vec, CanonicalMap};
+///
+/// let map: HashMap<u8, &str> = [(2, "b"), (1, "a")].into();
+/// let packed = pack_vec(&CanonicalMap(map)).unwrap();
+/// assert_eq!(packed, [0x82, 0x01, 0xa1, b'a', 0x02, 0xa1, b'b']);
+/// # }
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CanonicalMap<M>(pub M);
+
+/// An entry of a map, as returned when iterating over a reference to the map.
+///
+/// This is implemented for `(&K, &V)`, and is used to pack a [CanonicalMap].
+pub trait MapEntry<'a> {
+    /// The type of the keys.
+    type Key: MsgPack + 'a;
+
+    /// The type of the values.
+    type Value: MsgPack + 'a;
+
+    /// Returns references to the key and the value.
+    fn into_entry(self) -> (&'a Self::Key, &'a Self::Value);
+}
+
+impl<'a, K: MsgPack + 'a, V: MsgPack + 'a> MapEntry<'a> for (&'a K, &'a V) {
+    type Key = K;
+    type Value = V;
+
+    fn into_entry(self) -> (&'a K, &'a V) {
+        self
+    }
+}
+
+impl<M> MsgPack for CanonicalMap<M>
+where
+    for<'a> &'a M: IntoIterator,
+    for<'a> <&'a M as IntoIterator>::Item: MapEntry<'a>,
+{
+    fn pack(&self) -> impl Iterator<Item = Piece<'_>> {
+        pack_map_sorted(self.0.into_iter().map(MapEntry::into_entry))
+    }
+}
+
+impl<'buf, M: MsgUnpack<'buf>> MsgUnpack<'buf> for CanonicalMap<M> {
+    fn unpack(bytes: &mut &'buf [u8]) -> Result<Self, UnpackErr>
+    where
+        Self: Sized,
+    {
+        Self::unpack_with_ctx(bytes, &mut UnpackCtx::new())
+    }
+
+    fn unpack_with_ctx(bytes: &mut &'buf [u8], ctx: &mut UnpackCtx) -> Result<Self, UnpackErr>
+    where
+        Self: Sized,
+    {
+        M::unpack_with_ctx(bytes, ctx).map(CanonicalMap)
+    }
+}
+
+impl<M> From<M> for CanonicalMap<M> {
+    fn from(map: M) -> Self {
+        CanonicalMap(map)
+    }
+}
+
+impl<M> Deref for CanonicalMap<M> {
+    type Target = M;
+
+    fn deref(&self) -> &M {
+        &self.0
+    }
+}
+
+impl<M> DerefMut for CanonicalMap<M> {
+    fn deref_mut(&mut self) -> &mut M {
+        &mut self.0
+    }
+}
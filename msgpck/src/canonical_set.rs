@@ -0,0 +1,91 @@
+use core::ops::{Deref, DerefMut};
+
+use crate::{util::pack_array_sorted, MsgPack, MsgUnpack, Piece, UnpackCtx, UnpackErr};
+
+/// A wrapper around a set which is packed deterministically.
+///
+/// The elements are sorted by their packed bytes, like the keys of a
+/// [CanonicalMap](crate::CanonicalMap).
+///
+/// [MsgPack] is implemented for any set that can be iterated over by reference, i.e. where `&S`
+/// implements `IntoIterator<Item = &T>`, e.g. `HashSet` or `BTreeSet`.
+///
+/// Unpacking is done the same way as for the inner set.
+///
+/// ```
+/// # #[cfg(feature = "std")] {
+/// use std::collections::HashSet;
+/// use msgpck::{pack_vec, CanonicalSet};
+///
+/// let set: HashSet<&str> = ["b", "a"].into();
+/// let packed = pack_vec(&CanonicalSet(set)).unwrap();
+/// assert_eq!(packed, [0x92, 0xa1, b'a', 0xa1, b'b']);
+/// # }
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CanonicalSet<S>(pub S);
+
+/// An element of a set, as returned when iterating over a reference to the set.
+///
+/// This is implemented for `&T`, and is used to pack a [CanonicalSet].
+pub trait SetElement<'a> {
+    /// The type of the elements.
+    type Element: MsgPack + 'a;
+
+    /// Returns a reference to the element.
+    fn into_element(self) -> &'a Self::Element;
+}
+
+impl<'a, T: MsgPack + 'a> SetElement<'a> for &'a T {
+    type Element = T;
+
+    fn into_element(self) -> &'a T {
+        self
+    }
+}
+
+impl<S> MsgPack for CanonicalSet<S>
+where
+    for<'a> &'a S: IntoIterator,
+    for<'a> <&'a S as IntoIterator>::Item: SetElement<'a>,
+{
+    fn pack(&self) -> impl Iterator<Item = Piece<'_>> {
+        pack_array_sorted(self.0.into_iter().map(SetElement::into_element))
+    }
+}
+
+impl<'buf, S: MsgUnpack<'buf>> MsgUnpack<'buf> for CanonicalSet<S> {
+    fn unpack(bytes: &mut &'buf [u8]) -> Result<Self, UnpackErr>
+    where
+        Self: Sized,
+    {
+        Self::unpack_with_ctx(bytes, &mut UnpackCtx::new())
+    }
+
+    fn unpack_with_ctx(bytes: &mut &'buf [u8], ctx: &mut UnpackCtx) -> Result<Self, UnpackErr>
+    where
+        Self: Sized,
+    {
+        S::unpack_with_ctx(bytes, ctx).map(CanonicalSet)
+    }
+}
+
+impl<S> From<S> for CanonicalSet<S> {
+    fn from(set: S) -> Self {
+        CanonicalSet(set)
+    }
+}
+
+impl<S> Deref for CanonicalSet<S> {
+    type Target = S;
+
+    fn deref(&self) -> &S {
+        &self.0
+    }
+}
+
+impl<S> DerefMut for CanonicalSet<S> {
+    fn deref_mut(&mut self) -> &mut S {
+        &mut self.0
+    }
+}
@@ -0,0 +1,148 @@
+//! State that is passed along while unpacking nested values.
+
+use crate::{ContextErr, MsgUnpack, PathSegment, UnpackErr};
+
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+/// How strictly values are unpacked, see [UnpackCtx::with_mode].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum UnpackMode {
+    /// Numbers may be unpacked from any marker with a value that fits the target type, e.g.
+    /// non-negative signed integers as unsigned integers, or integers as floats.
+    #[default]
+    Lenient,
+
+    /// Numbers are only unpacked from the exact kind of marker as the target type, e.g. signed
+    /// integers aren't unpacked as unsigned integers, and integers aren't unpacked as floats.
+    Strict,
+
+    /// Like [UnpackMode::Strict], but values must also be encoded canonically, i.e. using the
+    /// smallest possible marker, and the keys of maps must be sorted by their packed bytes without
+    /// duplicates, like [CanonicalMap](crate::CanonicalMap). Otherwise
+    /// [UnpackErr::NonCanonical] is returned.
+    Canonical,
+}
+
+/// The context of unpacking a value, which is passed to [MsgUnpack::unpack_with_ctx].
+///
+/// It records the path to the value that failed to unpack and the offset where that value starts,
+/// which are returned as a [ContextErr] by [UnpackCtx::unpack_slice]. Paths are only recorded
+/// with the `alloc` feature.
+///
+/// ```
+/// # #[cfg(feature = "alloc")] {
+/// use msgpck::{MsgUnpack, PathSegment, UnpackCtx};
+///
+/// #[derive(Debug, MsgUnpack)]
+/// struct Reading {
+///     sensor: u8,
+///     values: Vec<u16>,
+/// }
+///
+/// // the second value is a string
+/// let packed = [0x92, 0x01, 0x92, 0x10, 0xa1, b'x'];
+/// let e = UnpackCtx::new().unpack_slice::<Reading>(&packed).unwrap_err();
+/// assert_eq!(
+///     e.path(),
+///     [
+///         PathSegment::Type("Reading"),
+///         PathSegment::Field("values"),
+///         PathSegment::Index(1),
+///     ]
+/// );
+/// assert_eq!(e.offset, 4);
+/// # }
+/// ```
+#[derive(Debug, Default)]
+pub struct UnpackCtx {
+    /// How strictly values are unpacked.
+    mode: UnpackMode,
+
+    /// The path to the value that failed to unpack, innermost first.
+    #[cfg(feature = "alloc")]
+    path: Vec<PathSegment>,
+
+    /// The number of bytes that were left at the start of the innermost value in the path.
+    error_start: Option<usize>,
+}
+
+impl UnpackCtx {
+    /// Create a new context, which unpacks values in [UnpackMode::Lenient] mode.
+    pub fn new() -> Self {
+        UnpackCtx::default()
+    }
+
+    /// Create a new context, which unpacks values in the given mode.
+    pub fn with_mode(mode: UnpackMode) -> Self {
+        UnpackCtx {
+            mode,
+            #[cfg(feature = "alloc")]
+            path: Vec::new(),
+            error_start: None,
+        }
+    }
+
+    /// How strictly values are unpacked.
+    pub fn mode(&self) -> UnpackMode {
+        self.mode
+    }
+
+    /// `true` if numbers may only be unpacked from the exact kind of marker as the target type.
+    pub fn is_strict(&self) -> bool {
+        self.mode != UnpackMode::Lenient
+    }
+
+    /// `true` if only canonically encoded values are unpacked, see [UnpackMode::Canonical].
+    pub fn is_canonical(&self) -> bool {
+        self.mode == UnpackMode::Canonical
+    }
+
+    /// Record that `error` was returned while unpacking the value at `segment`, which started at
+    /// `start`, and return it.
+    ///
+    /// This must be called on the way out of nested values, i.e. innermost first. The offset of
+    /// the error is where the innermost value started, regardless of how much of it was read.
+    pub fn error_at(&mut self, segment: PathSegment, start: &[u8], error: UnpackErr) -> UnpackErr {
+        self.error_start.get_or_insert(start.len());
+
+        #[cfg(feature = "alloc")]
+        self.path.push(segment);
+
+        #[cfg(not(feature = "alloc"))]
+        let _ = segment;
+
+        error
+    }
+
+    /// Unpack a [MsgUnpack] type from a byte slice using this context.
+    ///
+    /// # Returns
+    /// If unpacking fails, this returns the error along with the path to the value that failed to
+    /// unpack, and the offset in `bytes` where that value starts. If there are bytes left after
+    /// the value, the offset is where they start.
+    pub fn unpack_slice<'a, T: MsgUnpack<'a>>(mut self, bytes: &'a [u8]) -> Result<T, ContextErr> {
+        let mut remaining = bytes;
+        let result = match T::unpack_with_ctx(&mut remaining, &mut self) {
+            Ok(_) if !remaining.is_empty() => {
+                self.error_start = Some(remaining.len());
+                Err(UnpackErr::TrailingBytes(remaining.len()))
+            }
+            result => result,
+        };
+
+        result.map_err(|error| {
+            // the path was recorded innermost first
+            #[cfg(feature = "alloc")]
+            self.path.reverse();
+
+            ContextErr {
+                error,
+                #[cfg(feature = "alloc")]
+                path: self.path,
+                offset: bytes.len() - self.error_start.unwrap_or(bytes.len()),
+            }
+        })
+    }
+}
@@ -1,8 +1,8 @@
 use crate::{
     helpers::pack_i64,
-    marker::Marker,
-    util::{unpack_map_header, Either},
-    MsgPack, MsgUnpack, PackErr, Piece, UnpackErr, Write,
+    marker::{ExpectedKind, Marker},
+    util::{unpack_map_header_with_ctx, Either},
+    MsgPack, MsgUnpack, PackErr, Piece, UnpackCtx, UnpackErr, Write,
 };
 
 /// The header/key of a msgpack-encoded enum value.
@@ -102,6 +102,16 @@ pub fn pack_enum_header_to_writer(
 /// **NOTE**: This function does not necessarily unpack a complete msgpack value.
 /// In the case of an enum with fields, the next value unpacked must be the fields of the enum.
 pub fn unpack_enum_header<'a>(bytes: &mut &'a [u8]) -> Result<EnumHeader<'a>, UnpackErr> {
+    unpack_enum_header_with_ctx(bytes, &mut UnpackCtx::new())
+}
+
+/// Like [unpack_enum_header], but unpacks the discriminant using `ctx`, e.g. so that a map
+/// header that isn't in its shortest form is rejected in
+/// [UnpackMode::Canonical](crate::UnpackMode::Canonical).
+pub fn unpack_enum_header_with_ctx<'a>(
+    bytes: &mut &'a [u8],
+    ctx: &mut UnpackCtx,
+) -> Result<EnumHeader<'a>, UnpackErr> {
     match bytes
         .first()
         .map(|&b| Marker::from_u8(b))
@@ -111,7 +121,7 @@ pub fn unpack_enum_header<'a>(bytes: &mut &'a [u8]) -> Result<EnumHeader<'a>, Un
         // decode the discriminant/name and return early.
         Marker::FixStr(..) | Marker::Str8 | Marker::Str16 | Marker::Str32 => {
             return Ok(EnumHeader {
-                variant: Variant::Name(MsgUnpack::unpack(bytes)?),
+                variant: Variant::Name(MsgUnpack::unpack_with_ctx(bytes, ctx)?),
                 unit: true,
             });
         }
@@ -127,19 +137,24 @@ pub fn unpack_enum_header<'a>(bytes: &mut &'a [u8]) -> Result<EnumHeader<'a>, Un
         | Marker::U32
         | Marker::U64 => {
             return Ok(EnumHeader {
-                variant: Variant::Discriminant(MsgUnpack::unpack(bytes)?),
+                variant: Variant::Discriminant(MsgUnpack::unpack_with_ctx(bytes, ctx)?),
                 unit: true,
             });
         }
 
         // if the enum is a map, it has at least 1 field.
         Marker::FixMap(_) | Marker::Map16 | Marker::Map32 => {
-            let len = unpack_map_header(bytes)?;
+            let len = unpack_map_header_with_ctx(bytes, ctx)?;
             if len != 1 {
                 return Err(UnpackErr::InvalidEnumHeader);
             }
         }
-        m => return Err(UnpackErr::WrongMarker(m)),
+        m => {
+            return Err(UnpackErr::wrong_marker(
+                m,
+                ExpectedKind::UINT | ExpectedKind::INT | ExpectedKind::STR | ExpectedKind::MAP,
+            ))
+        }
     }
 
     // read the discriminant/name from the map key
@@ -149,12 +164,17 @@ pub fn unpack_enum_header<'a>(bytes: &mut &'a [u8]) -> Result<EnumHeader<'a>, Un
         .ok_or(UnpackErr::UnexpectedEof)?
     {
         Marker::FixPos(..) | Marker::U8 | Marker::U16 | Marker::U32 | Marker::U64 => {
-            Variant::Discriminant(MsgUnpack::unpack(bytes)?)
+            Variant::Discriminant(MsgUnpack::unpack_with_ctx(bytes, ctx)?)
         }
         Marker::FixStr(..) | Marker::Str8 | Marker::Str16 | Marker::Str32 => {
-            Variant::Name(MsgUnpack::unpack(bytes)?)
+            Variant::Name(MsgUnpack::unpack_with_ctx(bytes, ctx)?)
+        }
+        m => {
+            return Err(UnpackErr::wrong_marker(
+                m,
+                ExpectedKind::UINT | ExpectedKind::STR,
+            ))
         }
-        m => return Err(UnpackErr::WrongMarker(m)),
     };
 
     Ok(EnumHeader {
@@ -1,7 +1,14 @@
-use crate::marker::Marker;
+use crate::marker::{ExpectedKind, Marker};
+use core::fmt::{self, Display};
 use core::num::TryFromIntError;
 use core::str::Utf8Error;
 
+#[cfg(feature = "alloc")]
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+
 #[derive(Debug)]
 #[cfg_attr(feature = "std", derive(thiserror::Error))]
 #[non_exhaustive]
@@ -34,8 +41,15 @@ pub enum UnpackErr {
     #[cfg_attr(feature = "std", error("Unexpected EOF"))]
     UnexpectedEof,
 
-    #[cfg_attr(feature = "std", error("Wrong marker, got {0:?}"))]
-    WrongMarker(Marker),
+    /// Found a value of the wrong kind.
+    #[cfg_attr(
+        feature = "std",
+        error("Wrong marker, expected {expected}, got {found:?}")
+    )]
+    WrongMarker {
+        found: Marker,
+        expected: ExpectedKind,
+    },
 
     #[cfg_attr(
         feature = "std",
@@ -43,6 +57,55 @@ pub enum UnpackErr {
     )]
     IntTooBig(TryFromIntError),
 
+    /// Found zero while unpacking a `NonZero` integer.
+    #[cfg_attr(
+        feature = "std",
+        error("Encountered zero while unpacking a non-zero integer.")
+    )]
+    UnexpectedZero,
+
+    /// Found a string that didn't contain exactly one character while unpacking a `char`.
+    #[cfg_attr(
+        feature = "std",
+        error("Expected a string with exactly one character.")
+    )]
+    InvalidChar,
+
+    /// Found a bin that wasn't exactly 16 bytes long while unpacking a 128-bit integer.
+    #[cfg_attr(
+        feature = "std",
+        error("Expected a bin of 16 bytes for a 128-bit integer.")
+    )]
+    InvalidInt128,
+
+    /// Found a bin of the wrong length while unpacking a fixed-size value, e.g. a `[u8; N]`.
+    #[cfg_attr(
+        feature = "std",
+        error("Expected a bin of {expected} bytes, got {found} bytes.")
+    )]
+    InvalidLength { expected: usize, found: usize },
+
+    /// Found an ext value that isn't a valid msgpack timestamp, e.g. because of its ext type or
+    /// length, or because it had too many nanoseconds.
+    #[cfg_attr(feature = "std", error("Invalid timestamp extension."))]
+    InvalidTimestamp,
+
+    /// A value wasn't encoded canonically, i.e. using the smallest possible marker, or the keys of a
+    /// map weren't sorted or contained duplicates. Only returned in
+    /// [UnpackMode::Canonical](crate::UnpackMode::Canonical) mode.
+    #[cfg_attr(
+        feature = "std",
+        error("Encountered a value that isn't encoded canonically.")
+    )]
+    NonCanonical,
+
+    /// A number couldn't be represented exactly by the float type that it was unpacked as.
+    #[cfg_attr(
+        feature = "std",
+        error("Encountered a number that can't be represented exactly as a float.")
+    )]
+    LossyFloat,
+
     #[cfg_attr(
         feature = "std",
         error("There were {0} bytes remaining after unpacking.")
@@ -78,10 +141,145 @@ pub enum UnpackErr {
     #[cfg_attr(feature = "std", error("Error unpacking enum: Invalid header."))]
     InvalidEnumHeader,
 
+    /// A `#[msgpck(try_from = "T")]` conversion failed.
+    #[cfg_attr(feature = "std", error("Conversion failed: {0}"))]
+    Conversion(ConversionErr),
+
+    /// A value was rejected by a `#[msgpck(validate = "path")]` function.
+    #[cfg_attr(feature = "std", error("Invalid value: {message}"))]
+    Invalid { message: &'static str },
+
     #[cfg_attr(feature = "std", error("{0}"))]
     Other(&'static str),
 }
 
+impl UnpackErr {
+    /// Create an [UnpackErr::WrongMarker].
+    pub const fn wrong_marker(found: Marker, expected: ExpectedKind) -> Self {
+        UnpackErr::WrongMarker { found, expected }
+    }
+}
+
+/// One step of the path to a value that failed to unpack, see [ContextErr::path].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PathSegment {
+    /// A type with the given name, e.g. a struct or an enum.
+    Type(&'static str),
+
+    /// A variant of an enum.
+    Variant(&'static str),
+
+    /// A named field of a struct or enum variant.
+    Field(&'static str),
+
+    /// An element of an array or map, or an unnamed field.
+    Index(usize),
+}
+
+/// An [UnpackErr] together with information about where it occurred.
+///
+/// This is returned by [unpack_slice_with_context](crate::unpack_slice_with_context).
+#[derive(Debug)]
+pub struct ContextErr {
+    /// The original error.
+    pub error: UnpackErr,
+
+    /// The path to the value that failed to unpack, starting at the outermost type.
+    #[cfg(feature = "alloc")]
+    pub(crate) path: Vec<PathSegment>,
+
+    /// The offset in the buffer where the value that failed to unpack starts, i.e. the innermost
+    /// value in the path. For [UnpackErr::TrailingBytes], this is where the trailing bytes start.
+    pub offset: usize,
+}
+
+impl ContextErr {
+    /// Get the path to the value that failed to unpack, starting at the outermost type.
+    ///
+    /// The path is only recorded with the `alloc` feature, and is always empty without it.
+    pub fn path(&self) -> &[PathSegment] {
+        #[cfg(feature = "alloc")]
+        return &self.path;
+
+        #[cfg(not(feature = "alloc"))]
+        return &[];
+    }
+}
+
+impl From<ContextErr> for UnpackErr {
+    fn from(e: ContextErr) -> Self {
+        e.error
+    }
+}
+
+impl Display for ContextErr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // only the outermost type is included, the names of the fields are usually enough
+        for (i, segment) in self.path().iter().enumerate() {
+            match segment {
+                PathSegment::Type(name) if i == 0 => write!(f, "{name}")?,
+                PathSegment::Type(_) => {}
+                PathSegment::Variant(name) => write!(f, "::{name}")?,
+                PathSegment::Field(name) => write!(f, ".{name}")?,
+                PathSegment::Index(index) => write!(f, "[{index}]")?,
+            }
+        }
+
+        if self.path().is_empty() {
+            write!(f, "byte {}", self.offset)?;
+        } else {
+            write!(f, " (byte {})", self.offset)?;
+        }
+
+        #[cfg(feature = "std")]
+        return write!(f, ": {}", self.error);
+
+        #[cfg(not(feature = "std"))]
+        return write!(f, ": {:?}", self.error);
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ContextErr {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.error)
+    }
+}
+
+/// The error of a failed conversion, e.g. from `#[msgpck(try_from = "T")]`.
+#[derive(Debug)]
+pub struct ConversionErr {
+    /// The type name of the original error.
+    pub error_type: &'static str,
+
+    /// The original error, formatted using `Display`.
+    #[cfg(feature = "alloc")]
+    pub message: String,
+}
+
+impl ConversionErr {
+    pub fn new<E: Display>(error: E) -> Self {
+        #[cfg(not(feature = "alloc"))]
+        let _ = error;
+
+        ConversionErr {
+            error_type: core::any::type_name::<E>(),
+            #[cfg(feature = "alloc")]
+            message: error.to_string(),
+        }
+    }
+}
+
+impl Display for ConversionErr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        #[cfg(feature = "alloc")]
+        return write!(f, "{}", self.message);
+
+        #[cfg(not(feature = "alloc"))]
+        return write!(f, "{}", self.error_type);
+    }
+}
+
 impl From<TryFromIntError> for UnpackErr {
     fn from(e: TryFromIntError) -> Self {
         UnpackErr::IntTooBig(e)
@@ -0,0 +1,173 @@
+//! Traits for packing/unpacking the fields of a struct without an array or map header.
+
+use crate::{PackErr, Piece, UnpackCtx, UnpackErr, Write};
+
+/// Pack the fields of a struct, without the array header.
+///
+/// This is implemented by `derive(MsgPack)` for structs, and is used to pack fields marked with
+/// `#[msgpck(flatten)]` into the array of the outer struct.
+pub trait PackFields {
+    /// The number of fields that are packed by [PackFields::pack_fields].
+    const FIELD_COUNT: usize;
+
+    /// Returns an iterator of msgpack [Piece]s for all the fields.
+    fn pack_fields(&self) -> impl Iterator<Item = Piece<'_>>;
+
+    /// Pack all the fields into a [Write], and return how many bytes were packed.
+    fn pack_fields_with_writer(&self, w: &mut dyn Write) -> Result<usize, PackErr>;
+}
+
+/// Unpack the fields of a struct, without the array header.
+///
+/// This is implemented by `derive(MsgUnpack)` for structs, and is used to unpack fields marked
+/// with `#[msgpck(flatten)]` from the array of the outer struct.
+pub trait UnpackFields<'buf> {
+    /// The number of fields that are unpacked by [UnpackFields::unpack_fields].
+    const FIELD_COUNT: usize;
+
+    /// Unpack all the fields from a msgpack bytes slice, using `ctx` for the fields.
+    fn unpack_fields(bytes: &mut &'buf [u8], ctx: &mut UnpackCtx) -> Result<Self, UnpackErr>
+    where
+        Self: Sized;
+}
+
+/// Pack the fields of a struct with `#[msgpck(key = N)]` as map entries, without the map header.
+///
+/// This is implemented by `derive(MsgPack)` for keyed structs, and is used to pack fields marked
+/// with `#[msgpck(flatten)]` into the map of the outer struct.
+pub trait PackMapFields {
+    /// The keys of all the fields, including those of flattened fields.
+    const KEYS: MapKeys;
+
+    /// Returns the number of map entries, and an iterator of msgpack [Piece]s for their keys and
+    /// values, in the order of their keys.
+    ///
+    /// Each piece is tagged with the key of its entry, so that the entries can be merged with
+    /// those of the outer struct. The number of entries isn't known until runtime, since fields
+    /// may be left out by `#[msgpck(skip_if)]`.
+    fn pack_map_fields(&self) -> (usize, impl Iterator<Item = (u64, Piece<'_>)>);
+}
+
+/// Unpack the fields of a struct with `#[msgpck(key = N)]` from map entries, without the map
+/// header.
+///
+/// This is implemented by `derive(MsgUnpack)` for keyed structs, and is used to unpack fields
+/// marked with `#[msgpck(flatten)]` from the map of the outer struct, where the entries may appear
+/// in any order.
+pub trait UnpackMapFields<'buf> {
+    /// The keys of all the fields, including those of flattened fields.
+    const KEYS: MapKeys;
+
+    /// The fields that have been unpacked so far.
+    type MapFields;
+
+    /// Returns [UnpackMapFields::MapFields] where no fields have been unpacked yet.
+    fn empty_map_fields() -> Self::MapFields;
+
+    /// Unpack the value of the field with the given key into `fields`, using `ctx` for the field.
+    ///
+    /// Returns `false` without consuming any bytes if the key doesn't belong to any field.
+    fn unpack_map_field(
+        fields: &mut Self::MapFields,
+        key: u64,
+        bytes: &mut &'buf [u8],
+        ctx: &mut UnpackCtx,
+    ) -> Result<bool, UnpackErr>;
+
+    /// Create the struct from the unpacked fields, where missing fields are set to
+    /// `Default::default()`, using `ctx` for the flattened fields.
+    ///
+    /// `map` is where the map that contained the fields starts, which is where errors that don't
+    /// belong to a single entry are reported.
+    fn from_map_fields(
+        fields: Self::MapFields,
+        map: &'buf [u8],
+        ctx: &mut UnpackCtx,
+    ) -> Result<Self, UnpackErr>
+    where
+        Self: Sized;
+}
+
+/// The keys of the fields of a struct with `#[msgpck(key = N)]`, including the keys of its
+/// flattened fields.
+///
+/// The derives check that no key is used twice, so a struct can't be flattened into another one
+/// with overlapping keys:
+///
+/// ```compile_fail
+/// # use msgpck::{MsgPack, MsgUnpack};
+/// #[derive(MsgPack, MsgUnpack)]
+/// struct Header {
+///     #[msgpck(key = 0)]
+///     version: u8,
+/// }
+///
+/// #[derive(MsgPack, MsgUnpack)]
+/// struct Message {
+///     #[msgpck(key = 0)]
+///     id: u8,
+///     #[msgpck(flatten)]
+///     header: Header,
+/// }
+///
+/// let message = Message { id: 1, header: Header { version: 2 } };
+/// let mut buf = [0u8; 16];
+/// msgpck::pack_slice(&mut buf, &message).unwrap();
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct MapKeys {
+    /// The keys of the fields of the struct itself.
+    pub keys: &'static [u64],
+
+    /// The keys of the flattened fields.
+    pub flattened: &'static [MapKeys],
+}
+
+impl MapKeys {
+    /// Returns how many times `key` is used.
+    pub const fn count(&self, key: u64) -> usize {
+        let mut count = 0;
+
+        let mut i = 0;
+        while i < self.keys.len() {
+            if self.keys[i] == key {
+                count += 1;
+            }
+            i += 1;
+        }
+
+        let mut i = 0;
+        while i < self.flattened.len() {
+            count += self.flattened[i].count(key);
+            i += 1;
+        }
+
+        count
+    }
+
+    /// Returns `true` if no key is used more than once.
+    pub const fn is_unique(&self) -> bool {
+        self.is_unique_in(self)
+    }
+
+    /// Returns `true` if none of these keys are used more than once in `all`.
+    const fn is_unique_in(&self, all: &MapKeys) -> bool {
+        let mut i = 0;
+        while i < self.keys.len() {
+            if all.count(self.keys[i]) > 1 {
+                return false;
+            }
+            i += 1;
+        }
+
+        let mut i = 0;
+        while i < self.flattened.len() {
+            if !self.flattened[i].is_unique_in(all) {
+                return false;
+            }
+            i += 1;
+        }
+
+        true
+    }
+}
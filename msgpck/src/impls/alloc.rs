@@ -1,12 +1,17 @@
 use core::ops::Deref;
 
 use crate::{
-    util::{pack_array, unpack_array},
-    MsgPack, MsgUnpack, Piece,
+    bytes::{unpack_bytes, UnpackBytes},
+    util::{pack_array, pack_map, unpack_array, unpack_map},
+    MsgPack, MsgUnpack, PackErr, Piece, UnpackCtx, UnpackErr, Write,
 };
-use alloc::borrow::ToOwned;
+use alloc::borrow::{Cow, ToOwned};
 use alloc::boxed::Box;
+use alloc::collections::{BTreeMap, BTreeSet, BinaryHeap, LinkedList, VecDeque};
+use alloc::rc::Rc;
 use alloc::string::String;
+#[cfg(target_has_atomic = "ptr")]
+use alloc::sync::Arc;
 use alloc::vec::Vec;
 
 impl<T: MsgPack> MsgPack for Vec<T> {
@@ -20,7 +25,17 @@ impl<'buf, T: MsgUnpack<'buf> + 'buf> MsgUnpack<'buf> for Vec<T> {
     where
         Self: Sized,
     {
-        unpack_array(bytes)
+        Self::unpack_with_ctx(bytes, &mut UnpackCtx::new())
+    }
+
+    fn unpack_with_ctx(
+        bytes: &mut &'buf [u8],
+        ctx: &mut UnpackCtx,
+    ) -> Result<Self, crate::UnpackErr>
+    where
+        Self: Sized,
+    {
+        unpack_array(bytes, ctx)
     }
 }
 
@@ -37,7 +52,17 @@ impl<'buf, T: MsgUnpack<'buf> + 'buf> MsgUnpack<'buf> for Box<T> {
     where
         Self: Sized,
     {
-        T::unpack(bytes).map(Box::new)
+        Self::unpack_with_ctx(bytes, &mut UnpackCtx::new())
+    }
+
+    fn unpack_with_ctx(
+        bytes: &mut &'buf [u8],
+        ctx: &mut UnpackCtx,
+    ) -> Result<Self, crate::UnpackErr>
+    where
+        Self: Sized,
+    {
+        T::unpack_with_ctx(bytes, ctx).map(Box::new)
     }
 }
 
@@ -54,7 +79,297 @@ impl<'buf> MsgUnpack<'buf> for String {
     where
         Self: Sized,
     {
-        let s: &str = MsgUnpack::unpack(bytes)?;
+        Self::unpack_with_ctx(bytes, &mut UnpackCtx::new())
+    }
+
+    fn unpack_with_ctx(
+        bytes: &mut &'buf [u8],
+        ctx: &mut UnpackCtx,
+    ) -> Result<Self, crate::UnpackErr>
+    where
+        Self: Sized,
+    {
+        let s: &str = MsgUnpack::unpack_with_ctx(bytes, ctx)?;
         Ok(s.to_owned())
     }
 }
+
+impl MsgPack for Box<str> {
+    #[inline(always)]
+    fn pack(&self) -> impl Iterator<Item = Piece<'_>> {
+        self.deref().pack()
+    }
+}
+
+impl<'buf> MsgUnpack<'buf> for Box<str> {
+    fn unpack(bytes: &mut &'buf [u8]) -> Result<Self, UnpackErr>
+    where
+        Self: Sized,
+    {
+        Self::unpack_with_ctx(bytes, &mut UnpackCtx::new())
+    }
+
+    fn unpack_with_ctx(bytes: &mut &'buf [u8], ctx: &mut UnpackCtx) -> Result<Self, UnpackErr>
+    where
+        Self: Sized,
+    {
+        let s: &str = MsgUnpack::unpack_with_ctx(bytes, ctx)?;
+        Ok(s.into())
+    }
+}
+
+/// Boxed slices are packed as arrays, like `Vec<T>`.
+impl<T: MsgPack> MsgPack for Box<[T]> {
+    fn pack(&self) -> impl Iterator<Item = Piece<'_>> {
+        pack_array(self.len(), self.iter())
+    }
+}
+
+impl<'buf, T: MsgUnpack<'buf> + 'buf> MsgUnpack<'buf> for Box<[T]> {
+    fn unpack(bytes: &mut &'buf [u8]) -> Result<Self, UnpackErr>
+    where
+        Self: Sized,
+    {
+        Self::unpack_with_ctx(bytes, &mut UnpackCtx::new())
+    }
+
+    fn unpack_with_ctx(bytes: &mut &'buf [u8], ctx: &mut UnpackCtx) -> Result<Self, UnpackErr>
+    where
+        Self: Sized,
+    {
+        Vec::unpack_with_ctx(bytes, ctx).map(Vec::into_boxed_slice)
+    }
+}
+
+impl<T: MsgPack + ?Sized> MsgPack for Rc<T> {
+    #[inline(always)]
+    fn pack(&self) -> impl Iterator<Item = Piece<'_>> {
+        self.deref().pack()
+    }
+
+    fn pack_with_writer(&self, w: &mut dyn Write) -> Result<usize, PackErr> {
+        self.deref().pack_with_writer(w)
+    }
+}
+
+impl<'buf, T: MsgUnpack<'buf>> MsgUnpack<'buf> for Rc<T> {
+    #[inline(always)]
+    fn unpack(bytes: &mut &'buf [u8]) -> Result<Self, UnpackErr>
+    where
+        Self: Sized,
+    {
+        Self::unpack_with_ctx(bytes, &mut UnpackCtx::new())
+    }
+
+    fn unpack_with_ctx(bytes: &mut &'buf [u8], ctx: &mut UnpackCtx) -> Result<Self, UnpackErr>
+    where
+        Self: Sized,
+    {
+        T::unpack_with_ctx(bytes, ctx).map(Rc::new)
+    }
+}
+
+#[cfg(target_has_atomic = "ptr")]
+impl<T: MsgPack + ?Sized> MsgPack for Arc<T> {
+    #[inline(always)]
+    fn pack(&self) -> impl Iterator<Item = Piece<'_>> {
+        self.deref().pack()
+    }
+
+    fn pack_with_writer(&self, w: &mut dyn Write) -> Result<usize, PackErr> {
+        self.deref().pack_with_writer(w)
+    }
+}
+
+#[cfg(target_has_atomic = "ptr")]
+impl<'buf, T: MsgUnpack<'buf>> MsgUnpack<'buf> for Arc<T> {
+    #[inline(always)]
+    fn unpack(bytes: &mut &'buf [u8]) -> Result<Self, UnpackErr>
+    where
+        Self: Sized,
+    {
+        Self::unpack_with_ctx(bytes, &mut UnpackCtx::new())
+    }
+
+    fn unpack_with_ctx(bytes: &mut &'buf [u8], ctx: &mut UnpackCtx) -> Result<Self, UnpackErr>
+    where
+        Self: Sized,
+    {
+        T::unpack_with_ctx(bytes, ctx).map(Arc::new)
+    }
+}
+
+impl MsgPack for Cow<'_, str> {
+    #[inline(always)]
+    fn pack(&self) -> impl Iterator<Item = Piece<'_>> {
+        self.deref().pack()
+    }
+}
+
+/// The string is borrowed from the buffer.
+impl<'a, 'buf: 'a> MsgUnpack<'buf> for Cow<'a, str> {
+    fn unpack(bytes: &mut &'buf [u8]) -> Result<Self, UnpackErr>
+    where
+        Self: Sized,
+    {
+        Self::unpack_with_ctx(bytes, &mut UnpackCtx::new())
+    }
+
+    fn unpack_with_ctx(bytes: &mut &'buf [u8], ctx: &mut UnpackCtx) -> Result<Self, UnpackErr>
+    where
+        Self: Sized,
+    {
+        <&str>::unpack_with_ctx(bytes, ctx).map(Cow::Borrowed)
+    }
+}
+
+/// Byte slices are packed as bin, like `[u8]`.
+impl MsgPack for Cow<'_, [u8]> {
+    #[inline(always)]
+    fn pack(&self) -> impl Iterator<Item = Piece<'_>> {
+        self.deref().pack()
+    }
+}
+
+/// The bytes are borrowed from the buffer if they were packed as bin, and copied if they were packed
+/// as an array.
+impl<'a, 'buf: 'a> MsgUnpack<'buf> for Cow<'a, [u8]> {
+    fn unpack(bytes: &mut &'buf [u8]) -> Result<Self, UnpackErr>
+    where
+        Self: Sized,
+    {
+        Self::unpack_with_ctx(bytes, &mut UnpackCtx::new())
+    }
+
+    fn unpack_with_ctx(bytes: &mut &'buf [u8], ctx: &mut UnpackCtx) -> Result<Self, UnpackErr>
+    where
+        Self: Sized,
+    {
+        unpack_bytes(bytes, ctx)
+    }
+}
+
+impl<'a, 'buf: 'a> UnpackBytes<'buf> for Cow<'a, [u8]> {
+    fn from_bin(bin: &'buf [u8]) -> Result<Self, UnpackErr> {
+        Ok(Cow::Borrowed(bin))
+    }
+
+    fn unpack_array(bytes: &mut &'buf [u8], ctx: &mut UnpackCtx) -> Result<Self, UnpackErr> {
+        Vec::unpack_with_ctx(bytes, ctx).map(Cow::Owned)
+    }
+}
+
+impl<K: MsgPack, V: MsgPack> MsgPack for BTreeMap<K, V> {
+    fn pack(&self) -> impl Iterator<Item = Piece<'_>> {
+        pack_map(self.len(), self.iter())
+    }
+}
+
+impl<'buf, K, V> MsgUnpack<'buf> for BTreeMap<K, V>
+where
+    K: MsgUnpack<'buf> + Ord,
+    V: MsgUnpack<'buf>,
+{
+    fn unpack(bytes: &mut &'buf [u8]) -> Result<Self, UnpackErr>
+    where
+        Self: Sized,
+    {
+        Self::unpack_with_ctx(bytes, &mut UnpackCtx::new())
+    }
+
+    fn unpack_with_ctx(bytes: &mut &'buf [u8], ctx: &mut UnpackCtx) -> Result<Self, UnpackErr>
+    where
+        Self: Sized,
+    {
+        unpack_map(bytes, ctx)
+    }
+}
+
+impl<T: MsgPack> MsgPack for BTreeSet<T> {
+    fn pack(&self) -> impl Iterator<Item = Piece<'_>> {
+        pack_array(self.len(), self.iter())
+    }
+}
+
+impl<'buf, T: MsgUnpack<'buf> + Ord> MsgUnpack<'buf> for BTreeSet<T> {
+    fn unpack(bytes: &mut &'buf [u8]) -> Result<Self, UnpackErr>
+    where
+        Self: Sized,
+    {
+        Self::unpack_with_ctx(bytes, &mut UnpackCtx::new())
+    }
+
+    fn unpack_with_ctx(bytes: &mut &'buf [u8], ctx: &mut UnpackCtx) -> Result<Self, UnpackErr>
+    where
+        Self: Sized,
+    {
+        unpack_array(bytes, ctx)
+    }
+}
+
+impl<T: MsgPack> MsgPack for VecDeque<T> {
+    fn pack(&self) -> impl Iterator<Item = Piece<'_>> {
+        pack_array(self.len(), self.iter())
+    }
+}
+
+impl<'buf, T: MsgUnpack<'buf>> MsgUnpack<'buf> for VecDeque<T> {
+    fn unpack(bytes: &mut &'buf [u8]) -> Result<Self, UnpackErr>
+    where
+        Self: Sized,
+    {
+        Self::unpack_with_ctx(bytes, &mut UnpackCtx::new())
+    }
+
+    fn unpack_with_ctx(bytes: &mut &'buf [u8], ctx: &mut UnpackCtx) -> Result<Self, UnpackErr>
+    where
+        Self: Sized,
+    {
+        unpack_array(bytes, ctx)
+    }
+}
+
+/// The elements are packed in an arbitrary order, see [BinaryHeap::iter].
+impl<T: MsgPack> MsgPack for BinaryHeap<T> {
+    fn pack(&self) -> impl Iterator<Item = Piece<'_>> {
+        pack_array(self.len(), self.iter())
+    }
+}
+
+impl<'buf, T: MsgUnpack<'buf> + Ord> MsgUnpack<'buf> for BinaryHeap<T> {
+    fn unpack(bytes: &mut &'buf [u8]) -> Result<Self, UnpackErr>
+    where
+        Self: Sized,
+    {
+        Self::unpack_with_ctx(bytes, &mut UnpackCtx::new())
+    }
+
+    fn unpack_with_ctx(bytes: &mut &'buf [u8], ctx: &mut UnpackCtx) -> Result<Self, UnpackErr>
+    where
+        Self: Sized,
+    {
+        unpack_array(bytes, ctx)
+    }
+}
+
+impl<T: MsgPack> MsgPack for LinkedList<T> {
+    fn pack(&self) -> impl Iterator<Item = Piece<'_>> {
+        pack_array(self.len(), self.iter())
+    }
+}
+
+impl<'buf, T: MsgUnpack<'buf>> MsgUnpack<'buf> for LinkedList<T> {
+    fn unpack(bytes: &mut &'buf [u8]) -> Result<Self, UnpackErr>
+    where
+        Self: Sized,
+    {
+        Self::unpack_with_ctx(bytes, &mut UnpackCtx::new())
+    }
+
+    fn unpack_with_ctx(bytes: &mut &'buf [u8], ctx: &mut UnpackCtx) -> Result<Self, UnpackErr>
+    where
+        Self: Sized,
+    {
+        unpack_array(bytes, ctx)
+    }
+}
@@ -0,0 +1,100 @@
+use core::mem::{self, MaybeUninit};
+
+use crate::{
+    bytes::unpack_bytes,
+    util::{pack_array, pack_array_header, unpack_array_header_with_ctx, write_pieces},
+    MsgPack, MsgUnpack, PackErr, PathSegment, Piece, UnpackCtx, UnpackErr, Write,
+};
+
+/// Arrays are packed as msgpack arrays, like in rmp_serde. Note that this includes `[u8; N]`,
+/// unlike `[u8]` which is packed as bin. Other slices can be packed as arrays using [Seq](crate::Seq).
+impl<T: MsgPack, const N: usize> MsgPack for [T; N] {
+    fn pack(&self) -> impl Iterator<Item = Piece<'_>> {
+        pack_array(N, self.iter())
+    }
+
+    fn pack_with_writer(&self, w: &mut dyn Write) -> Result<usize, PackErr> {
+        let mut n = write_pieces(pack_array_header(N), w)?;
+        for elem in self {
+            n += elem.pack_with_writer(w)?;
+        }
+        Ok(n)
+    }
+}
+
+impl<'buf, T: MsgUnpack<'buf>, const N: usize> MsgUnpack<'buf> for [T; N] {
+    /// Unpack the array from a msgpack array of exactly `N` elements, without allocating.
+    fn unpack(bytes: &mut &'buf [u8]) -> Result<Self, UnpackErr>
+    where
+        Self: Sized,
+    {
+        Self::unpack_with_ctx(bytes, &mut UnpackCtx::new())
+    }
+
+    fn unpack_with_ctx(bytes: &mut &'buf [u8], ctx: &mut UnpackCtx) -> Result<Self, UnpackErr>
+    where
+        Self: Sized,
+    {
+        let len = unpack_array_header_with_ctx(bytes, ctx)?;
+        check_len(len, N)?;
+
+        // SAFETY: an array of MaybeUninit doesn't need to be initialized
+        let mut elements: [MaybeUninit<T>; N] = unsafe { MaybeUninit::uninit().assume_init() };
+
+        // unpack the elements in place, and drop the unpacked ones if an error occurs
+        let mut guard = PartialArray {
+            elements: &mut elements,
+            len: 0,
+        };
+        for i in 0..N {
+            let start = *bytes;
+            let element = T::unpack_with_ctx(bytes, ctx)
+                .map_err(|e| ctx.error_at(PathSegment::Index(i), start, e))?;
+            guard.elements[i].write(element);
+            guard.len += 1;
+        }
+        mem::forget(guard);
+
+        // SAFETY: all elements were initialized, and [MaybeUninit<T>; N] has the same layout as [T; N]
+        Ok(unsafe {
+            (&elements as *const [MaybeUninit<T>; N])
+                .cast::<[T; N]>()
+                .read()
+        })
+    }
+}
+
+/// The first `len` initialized elements of an array, which are dropped with the guard.
+struct PartialArray<'a, T, const N: usize> {
+    elements: &'a mut [MaybeUninit<T>; N],
+    len: usize,
+}
+
+impl<T, const N: usize> Drop for PartialArray<'_, T, N> {
+    fn drop(&mut self) {
+        for element in &mut self.elements[..self.len] {
+            // SAFETY: the first `len` elements are initialized
+            unsafe { element.assume_init_drop() };
+        }
+    }
+}
+
+/// Helper function that unpacks a `[u8; N]` from either a bin or an array of exactly `N` bytes.
+///
+/// Byte arrays are packed as arrays, so this is mostly useful for fixed-size keys and hashes that
+/// were packed as bin by some other implementation. Use it with
+/// `#[msgpck(unpack_with = "msgpck::helpers::unpack_byte_array")]`.
+pub fn unpack_byte_array<const N: usize>(bytes: &mut &[u8]) -> Result<[u8; N], UnpackErr> {
+    unpack_bytes(bytes, &mut UnpackCtx::new())
+}
+
+/// Check that an array with `len` elements can be unpacked as an array with `expected` elements.
+pub(crate) fn check_len(len: usize, expected: usize) -> Result<(), UnpackErr> {
+    if len < expected {
+        return Err(UnpackErr::MissingFields { got: len, expected });
+    }
+    if len > expected {
+        return Err(UnpackErr::TooManyFields { got: len, expected });
+    }
+    Ok(())
+}
@@ -0,0 +1,59 @@
+use arrayvec::{ArrayString, ArrayVec};
+
+use crate::{
+    util::{pack_array, unpack_array_with},
+    MsgPack, MsgUnpack, Piece, UnpackCtx, UnpackErr,
+};
+
+impl<T, const CAP: usize> MsgPack for ArrayVec<T, CAP>
+where
+    T: MsgPack,
+{
+    fn pack(&self) -> impl Iterator<Item = Piece<'_>> {
+        pack_array(self.len(), self.iter())
+    }
+}
+
+impl<'buf, T, const CAP: usize> MsgUnpack<'buf> for ArrayVec<T, CAP>
+where
+    T: MsgUnpack<'buf>,
+{
+    fn unpack(bytes: &mut &'buf [u8]) -> Result<Self, UnpackErr>
+    where
+        Self: Sized,
+    {
+        Self::unpack_with_ctx(bytes, &mut UnpackCtx::new())
+    }
+
+    fn unpack_with_ctx(bytes: &mut &'buf [u8], ctx: &mut UnpackCtx) -> Result<Self, UnpackErr>
+    where
+        Self: Sized,
+    {
+        unpack_array_with(bytes, ctx, ArrayVec::new(), |vec, elem| {
+            vec.try_push(elem).map_err(|e| e.element())
+        })
+    }
+}
+
+impl<const CAP: usize> MsgPack for ArrayString<CAP> {
+    fn pack(&self) -> impl Iterator<Item = Piece<'_>> {
+        self.as_str().pack()
+    }
+}
+
+impl<'buf, const CAP: usize> MsgUnpack<'buf> for ArrayString<CAP> {
+    fn unpack(bytes: &mut &'buf [u8]) -> Result<Self, UnpackErr>
+    where
+        Self: Sized,
+    {
+        Self::unpack_with_ctx(bytes, &mut UnpackCtx::new())
+    }
+
+    fn unpack_with_ctx(bytes: &mut &'buf [u8], ctx: &mut UnpackCtx) -> Result<Self, UnpackErr>
+    where
+        Self: Sized,
+    {
+        let s: &str = MsgUnpack::unpack_with_ctx(bytes, ctx)?;
+        ArrayString::from(s).map_err(|_| UnpackErr::BufferOverflow)
+    }
+}
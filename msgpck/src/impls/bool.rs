@@ -1,4 +1,8 @@
-use crate::{marker::Marker, util::slice_take, MsgPack, MsgUnpack, Piece, UnpackErr};
+use crate::{
+    marker::{ExpectedKind, Marker},
+    util::slice_take,
+    MsgPack, MsgUnpack, Piece, UnpackErr,
+};
 use core::iter;
 
 impl MsgPack for bool {
@@ -17,7 +21,7 @@ impl<'buf> MsgUnpack<'buf> for bool {
         match Marker::from_u8(b) {
             Marker::True => Ok(true),
             Marker::False => Ok(false),
-            marker => Err(UnpackErr::WrongMarker(marker)),
+            marker => Err(UnpackErr::wrong_marker(marker, ExpectedKind::BOOL)),
         }
     }
 }
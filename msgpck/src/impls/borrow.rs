@@ -1,6 +1,6 @@
 use crate::{MsgPack, Piece};
 
-impl<'r, T> MsgPack for &'r T
+impl<T> MsgPack for &T
 where
     T: MsgPack,
 {
@@ -9,7 +9,7 @@ where
     }
 }
 
-impl<'r, T> MsgPack for &'r mut T
+impl<T> MsgPack for &mut T
 where
     T: MsgPack,
 {
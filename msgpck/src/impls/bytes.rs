@@ -1,21 +1,26 @@
-use crate::{marker::Marker, util::slice_take, MsgPack, MsgUnpack, Piece, UnpackErr};
+use crate::{
+    marker::{ExpectedKind, Marker},
+    util::slice_take,
+    MsgPack, MsgUnpack, Piece, UnpackCtx, UnpackErr,
+};
 
 impl MsgPack for [u8] {
     fn pack(&self) -> impl Iterator<Item = Piece<'_>> {
         let marker_piece;
         let len_piece;
 
-        match self.len() {
-            ..=0xff => {
-                marker_piece = Marker::Bin8.into();
+        let marker = bin_marker(self.len());
+        match marker {
+            Marker::Bin8 => {
+                marker_piece = marker.into();
                 len_piece = (self.len() as u8).into();
             }
-            ..=0xffff => {
-                marker_piece = Marker::Bin16.into();
+            Marker::Bin16 => {
+                marker_piece = marker.into();
                 len_piece = (self.len() as u16).into();
             }
             _ => {
-                marker_piece = Marker::Bin32.into();
+                marker_piece = marker.into();
                 len_piece = (self.len() as u32).into();
             }
         }
@@ -26,17 +31,27 @@ impl MsgPack for [u8] {
 
 impl<'buf> MsgUnpack<'buf> for &'buf [u8] {
     fn unpack(bytes: &mut &'buf [u8]) -> Result<Self, UnpackErr>
+    where
+        Self: Sized,
+    {
+        Self::unpack_with_ctx(bytes, &mut UnpackCtx::new())
+    }
+
+    fn unpack_with_ctx(bytes: &mut &'buf [u8], ctx: &mut UnpackCtx) -> Result<Self, UnpackErr>
     where
         Self: Sized,
     {
         let &[b] = slice_take(bytes)?;
-        let len: usize = match Marker::from_u8(b) {
+        let marker = Marker::from_u8(b);
+        let len: usize = match marker {
             Marker::Bin8 => slice_take::<_, 1>(bytes)?[0].into(),
             Marker::Bin16 => u16::from_be_bytes(*slice_take(bytes)?).into(),
             Marker::Bin32 => u32::from_be_bytes(*slice_take(bytes)?).try_into()?,
-            m => return Err(UnpackErr::WrongMarker(m)),
+            m => return Err(UnpackErr::wrong_marker(m, ExpectedKind::BIN)),
         };
 
+        crate::util::check_canonical(ctx, marker, bin_marker(len))?;
+
         if len > bytes.len() {
             return Err(UnpackErr::UnexpectedEof);
         }
@@ -47,3 +62,12 @@ impl<'buf> MsgUnpack<'buf> for &'buf [u8] {
         Ok(bin)
     }
 }
+
+/// The marker of a bin with the given length.
+const fn bin_marker(len: usize) -> Marker {
+    match len {
+        ..=0xff => Marker::Bin8,
+        ..=0xffff => Marker::Bin16,
+        _ => Marker::Bin32,
+    }
+}
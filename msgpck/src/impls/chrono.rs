@@ -0,0 +1,77 @@
+use chrono::{DateTime, NaiveDateTime, Utc};
+
+use crate::{
+    timestamp::{pack_timestamp, unpack_timestamp_or_str, TimestampOrStr},
+    ConversionErr, MsgPack, MsgUnpack, Piece, Timestamp, UnpackCtx, UnpackErr,
+};
+
+/// Date-times are packed using the msgpack timestamp extension type.
+///
+/// They may also be unpacked from an integer number of seconds since the Unix epoch, or from an
+/// RFC 3339 string like `2023-11-14T22:13:20Z`, except in
+/// [UnpackMode::Canonical](crate::UnpackMode::Canonical) mode.
+impl MsgPack for DateTime<Utc> {
+    fn pack(&self) -> impl Iterator<Item = Piece<'_>> {
+        pack_timestamp(to_timestamp(self))
+    }
+}
+
+impl<'buf> MsgUnpack<'buf> for DateTime<Utc> {
+    fn unpack(bytes: &mut &'buf [u8]) -> Result<Self, UnpackErr>
+    where
+        Self: Sized,
+    {
+        Self::unpack_with_ctx(bytes, &mut UnpackCtx::new())
+    }
+
+    fn unpack_with_ctx(bytes: &mut &'buf [u8], ctx: &mut UnpackCtx) -> Result<Self, UnpackErr>
+    where
+        Self: Sized,
+    {
+        match unpack_timestamp_or_str(bytes, ctx)? {
+            TimestampOrStr::Timestamp(ts) => {
+                DateTime::from_timestamp(ts.seconds(), ts.nanoseconds())
+                    .ok_or(UnpackErr::Other("timestamp out of range for DateTime"))
+            }
+            TimestampOrStr::Str(s) => DateTime::parse_from_rfc3339(s)
+                .map(|dt| dt.with_timezone(&Utc))
+                .map_err(|e| UnpackErr::Conversion(ConversionErr::new(e))),
+        }
+    }
+}
+
+/// Naive date-times are assumed to be in UTC, and are packed like a `DateTime<Utc>`.
+impl MsgPack for NaiveDateTime {
+    fn pack(&self) -> impl Iterator<Item = Piece<'_>> {
+        pack_timestamp(to_timestamp(&self.and_utc()))
+    }
+}
+
+impl<'buf> MsgUnpack<'buf> for NaiveDateTime {
+    fn unpack(bytes: &mut &'buf [u8]) -> Result<Self, UnpackErr>
+    where
+        Self: Sized,
+    {
+        Self::unpack_with_ctx(bytes, &mut UnpackCtx::new())
+    }
+
+    fn unpack_with_ctx(bytes: &mut &'buf [u8], ctx: &mut UnpackCtx) -> Result<Self, UnpackErr>
+    where
+        Self: Sized,
+    {
+        DateTime::<Utc>::unpack_with_ctx(bytes, ctx).map(|dt| dt.naive_utc())
+    }
+}
+
+fn to_timestamp(dt: &DateTime<Utc>) -> Timestamp {
+    let seconds = dt.timestamp();
+    let nanoseconds = dt.timestamp_subsec_nanos();
+
+    // chrono represents leap seconds as more than 1,000,000,000 nanoseconds
+    let (seconds, nanoseconds) = match nanoseconds.checked_sub(1_000_000_000) {
+        Some(leap) => (seconds + 1, leap),
+        None => (seconds, nanoseconds),
+    };
+
+    Timestamp::new(seconds, nanoseconds).expect("nanoseconds are less than a second")
+}
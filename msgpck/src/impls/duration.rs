@@ -0,0 +1,42 @@
+use core::time::Duration;
+
+use super::{arrays::check_len, uints::pack_u64};
+use crate::{
+    util::{pack_array_header, unpack_array_header_with_ctx},
+    MsgPack, MsgUnpack, Piece, UnpackCtx, UnpackErr,
+};
+
+/// Durations are packed as an array of `[secs, nanos]`, like in rmp_serde.
+impl MsgPack for Duration {
+    fn pack(&self) -> impl Iterator<Item = Piece<'_>> {
+        pack_duration(*self)
+    }
+}
+
+impl<'buf> MsgUnpack<'buf> for Duration {
+    fn unpack(bytes: &mut &'buf [u8]) -> Result<Self, UnpackErr>
+    where
+        Self: Sized,
+    {
+        Self::unpack_with_ctx(bytes, &mut UnpackCtx::new())
+    }
+
+    fn unpack_with_ctx(bytes: &mut &'buf [u8], ctx: &mut UnpackCtx) -> Result<Self, UnpackErr>
+    where
+        Self: Sized,
+    {
+        check_len(unpack_array_header_with_ctx(bytes, ctx)?, 2)?;
+        let secs = u64::unpack_with_ctx(bytes, ctx)?;
+        let nanos = u32::unpack_with_ctx(bytes, ctx)?;
+
+        Duration::from_secs(secs)
+            .checked_add(Duration::from_nanos(nanos.into()))
+            .ok_or(UnpackErr::Other("overflow unpacking Duration"))
+    }
+}
+
+pub(crate) fn pack_duration<'a>(d: Duration) -> impl Iterator<Item = Piece<'a>> {
+    pack_array_header(2)
+        .chain(pack_u64(d.as_secs()).pieces())
+        .chain(pack_u64(d.subsec_nanos().into()).pieces())
+}
@@ -1,4 +1,9 @@
-use crate::{marker::Marker, util::slice_take, MsgPack, MsgUnpack, Piece, UnpackErr};
+use super::{ints::unpack_i64, uints::unpack_u64};
+use crate::{
+    marker::{ExpectedKind, Marker},
+    util::slice_take,
+    MsgPack, MsgUnpack, Piece, UnpackCtx, UnpackErr,
+};
 
 impl MsgPack for f32 {
     fn pack(&self) -> impl Iterator<Item = Piece<'_>> {
@@ -7,18 +12,50 @@ impl MsgPack for f32 {
 }
 
 impl<'buf> MsgUnpack<'buf> for f32 {
-    fn unpack(bytes: &mut &'buf [u8]) -> Result<Self, crate::UnpackErr>
+    fn unpack(bytes: &mut &'buf [u8]) -> Result<Self, UnpackErr>
     where
         Self: Sized,
     {
-        let &[b] = slice_take(bytes)?;
+        Self::unpack_with_ctx(bytes, &mut UnpackCtx::new())
+    }
+
+    fn unpack_with_ctx(bytes: &mut &'buf [u8], ctx: &mut UnpackCtx) -> Result<Self, UnpackErr>
+    where
+        Self: Sized,
+    {
+        let &b = bytes.first().ok_or(UnpackErr::UnexpectedEof)?;
 
-        let marker = Marker::from_u8(b);
-        let Marker::F32 = marker else {
-            return Err(UnpackErr::WrongMarker(marker));
-        };
+        match Marker::from_u8(b) {
+            Marker::F32 => {
+                let _ = slice_take::<_, 1>(bytes);
+                Ok(f32::from_be_bytes(*slice_take(bytes)?))
+            }
 
-        Ok(f32::from_be_bytes(*slice_take(bytes)?))
+            m if ctx.is_strict() => Err(UnpackErr::wrong_marker(m, ExpectedKind::FLOAT)),
+
+            // f64 is accepted if it can be represented exactly as an f32
+            Marker::F64 => {
+                let _ = slice_take::<_, 1>(bytes);
+                let f = f64::from_be_bytes(*slice_take(bytes)?);
+                let f32 = f as f32;
+                if f64::from(f32) != f && !f.is_nan() {
+                    return Err(UnpackErr::LossyFloat);
+                }
+                Ok(f32)
+            }
+
+            // as are integers
+            m if matches!(ExpectedKind::of(m), ExpectedKind::UINT | ExpectedKind::INT) => {
+                let n = unpack_int(bytes, ctx)?;
+                let f = n as f32;
+                if f as i128 != n {
+                    return Err(UnpackErr::LossyFloat);
+                }
+                Ok(f)
+            }
+
+            m => Err(UnpackErr::wrong_marker(m, FLOAT_KINDS)),
+        }
     }
 }
 
@@ -29,17 +66,57 @@ impl MsgPack for f64 {
 }
 
 impl<'buf> MsgUnpack<'buf> for f64 {
-    fn unpack(bytes: &mut &'buf [u8]) -> Result<Self, crate::UnpackErr>
+    fn unpack(bytes: &mut &'buf [u8]) -> Result<Self, UnpackErr>
+    where
+        Self: Sized,
+    {
+        Self::unpack_with_ctx(bytes, &mut UnpackCtx::new())
+    }
+
+    fn unpack_with_ctx(bytes: &mut &'buf [u8], ctx: &mut UnpackCtx) -> Result<Self, UnpackErr>
     where
         Self: Sized,
     {
-        let &[b] = slice_take(bytes)?;
+        let &b = bytes.first().ok_or(UnpackErr::UnexpectedEof)?;
+
+        match Marker::from_u8(b) {
+            Marker::F64 => {
+                let _ = slice_take::<_, 1>(bytes);
+                Ok(f64::from_be_bytes(*slice_take(bytes)?))
+            }
+
+            m if ctx.is_strict() => Err(UnpackErr::wrong_marker(m, ExpectedKind::FLOAT)),
+
+            // f32 can always be represented exactly as an f64
+            Marker::F32 => {
+                let _ = slice_take::<_, 1>(bytes);
+                Ok(f32::from_be_bytes(*slice_take(bytes)?).into())
+            }
+
+            // integers are accepted if they can be represented exactly
+            m if matches!(ExpectedKind::of(m), ExpectedKind::UINT | ExpectedKind::INT) => {
+                let n = unpack_int(bytes, ctx)?;
+                let f = n as f64;
+                if f as i128 != n {
+                    return Err(UnpackErr::LossyFloat);
+                }
+                Ok(f)
+            }
+
+            m => Err(UnpackErr::wrong_marker(m, FLOAT_KINDS)),
+        }
+    }
+}
 
-        let marker = Marker::from_u8(b);
-        let Marker::F64 = marker else {
-            return Err(UnpackErr::WrongMarker(marker));
-        };
+/// The kinds of values accepted when unpacking floats.
+const FLOAT_KINDS: ExpectedKind = ExpectedKind::FLOAT
+    .union(ExpectedKind::UINT)
+    .union(ExpectedKind::INT);
 
-        Ok(f64::from_be_bytes(*slice_take(bytes)?))
+/// Unpack any integer, signed or unsigned.
+fn unpack_int(bytes: &mut &[u8], ctx: &UnpackCtx) -> Result<i128, UnpackErr> {
+    match bytes.first().map(|&b| ExpectedKind::of(Marker::from_u8(b))) {
+        Some(ExpectedKind::UINT) => Ok(unpack_u64(bytes, ctx)?.into()),
+        _ => Ok(unpack_i64(bytes, ctx)?.into()),
     }
 }
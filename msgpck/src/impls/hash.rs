@@ -0,0 +1,85 @@
+//! Implementations shared by `std` and `hashbrown` hash collections.
+
+/// Implement [MsgPack](crate::MsgPack) and [MsgUnpack](crate::MsgUnpack) for the `HashMap` and
+/// `HashSet` of a crate, with any hasher.
+///
+/// Use [CanonicalMap](crate::CanonicalMap) and [CanonicalSet](crate::CanonicalSet) to pack them
+/// deterministically.
+macro_rules! impl_hash_collections {
+    (collections: $(::$collections:ident)+ $(,)?) => {
+        use crate::{
+            util::{pack_array, pack_map, unpack_array, unpack_map},
+            MsgPack, MsgUnpack, Piece, UnpackCtx, UnpackErr,
+        };
+        use core::hash::{BuildHasher, Hash};
+        use $(::$collections)+::{HashMap, HashSet};
+
+        impl<K, V, S> MsgPack for HashMap<K, V, S>
+        where
+            K: MsgPack,
+            V: MsgPack,
+        {
+            fn pack(&self) -> impl Iterator<Item = Piece<'_>> {
+                pack_map(self.len(), self.iter())
+            }
+        }
+
+        impl<'buf, K, V, S> MsgUnpack<'buf> for HashMap<K, V, S>
+        where
+            K: MsgUnpack<'buf> + Hash + Eq,
+            V: MsgUnpack<'buf>,
+            S: BuildHasher + Default,
+        {
+            fn unpack(bytes: &mut &'buf [u8]) -> Result<Self, UnpackErr>
+            where
+                Self: Sized,
+            {
+                Self::unpack_with_ctx(bytes, &mut UnpackCtx::new())
+            }
+
+            fn unpack_with_ctx(
+                bytes: &mut &'buf [u8],
+                ctx: &mut UnpackCtx,
+            ) -> Result<Self, UnpackErr>
+            where
+                Self: Sized,
+            {
+                unpack_map(bytes, ctx)
+            }
+        }
+
+        impl<T, S> MsgPack for HashSet<T, S>
+        where
+            T: MsgPack,
+        {
+            fn pack(&self) -> impl Iterator<Item = Piece<'_>> {
+                pack_array(self.len(), self.iter())
+            }
+        }
+
+        impl<'buf, T, S> MsgUnpack<'buf> for HashSet<T, S>
+        where
+            T: MsgUnpack<'buf> + Hash + Eq,
+            S: BuildHasher + Default,
+        {
+            fn unpack(bytes: &mut &'buf [u8]) -> Result<Self, UnpackErr>
+            where
+                Self: Sized,
+            {
+                Self::unpack_with_ctx(bytes, &mut UnpackCtx::new())
+            }
+
+            fn unpack_with_ctx(
+                bytes: &mut &'buf [u8],
+                ctx: &mut UnpackCtx,
+            ) -> Result<Self, UnpackErr>
+            where
+                Self: Sized,
+            {
+                unpack_array(bytes, ctx)
+            }
+        }
+    };
+}
+
+pub(crate) use impl_hash_collections;
@@ -0,0 +1,3 @@
+super::hash::impl_hash_collections! {
+    collections: ::hashbrown,
+}
@@ -0,0 +1,384 @@
+//! Implementations shared by all supported versions of `heapless`.
+//!
+//! Each version gets its own module which invokes [impl_heapless], so supporting a new release of
+//! `heapless` is mostly a matter of adding a feature and a module.
+
+/// Implement [MsgPack](crate::MsgPack) and [MsgUnpack](crate::MsgUnpack) for the collections of a
+/// version of `heapless`, and define a `ByteBuf` type for it.
+///
+/// Unpacking a value that doesn't fit in a collection returns
+/// [UnpackErr::BufferOverflow](crate::UnpackErr::BufferOverflow).
+macro_rules! impl_heapless {
+    (
+        heapless: $heapless:ident,
+        history_buffer: $HistoryBuffer:ident,
+        hash: $Hash:path,
+        build_hasher: $BuildHasher:path $(,)?
+    ) => {
+        use crate::{
+            bytes::{unpack_bytes, UnpackBytes},
+            util::{
+                pack_array, pack_map, unpack_array_header_with_ctx, unpack_array_with,
+                unpack_map_with,
+            },
+            MsgPack, MsgUnpack, PackErr, Piece, UnpackCtx, UnpackErr, Write,
+        };
+        use ::$heapless::{
+            binary_heap::Kind, $HistoryBuffer, BinaryHeap, Deque, IndexMap, IndexSet, LinearMap,
+            String, Vec,
+        };
+        use core::ops::{Deref, DerefMut};
+
+        impl<T, const N: usize> MsgPack for Vec<T, N>
+        where
+            T: MsgPack,
+        {
+            fn pack(&self) -> impl Iterator<Item = Piece<'_>> {
+                pack_array(self.len(), self.iter())
+            }
+        }
+
+        impl<'buf, T, const N: usize> MsgUnpack<'buf> for Vec<T, N>
+        where
+            T: MsgUnpack<'buf> + 'buf,
+        {
+            fn unpack(bytes: &mut &'buf [u8]) -> Result<Self, UnpackErr>
+            where
+                Self: Sized,
+            {
+                Self::unpack_with_ctx(bytes, &mut UnpackCtx::new())
+            }
+
+            fn unpack_with_ctx(
+                bytes: &mut &'buf [u8],
+                ctx: &mut UnpackCtx,
+            ) -> Result<Self, UnpackErr>
+            where
+                Self: Sized,
+            {
+                unpack_array_with(bytes, ctx, Vec::new(), Vec::push)
+            }
+        }
+
+        impl<const N: usize> MsgPack for String<N> {
+            fn pack(&self) -> impl Iterator<Item = Piece<'_>> {
+                self.as_str().pack()
+            }
+        }
+
+        impl<'buf, const N: usize> MsgUnpack<'buf> for String<N> {
+            fn unpack(bytes: &mut &'buf [u8]) -> Result<Self, UnpackErr>
+            where
+                Self: Sized,
+            {
+                Self::unpack_with_ctx(bytes, &mut UnpackCtx::new())
+            }
+
+            fn unpack_with_ctx(
+                bytes: &mut &'buf [u8],
+                ctx: &mut UnpackCtx,
+            ) -> Result<Self, UnpackErr>
+            where
+                Self: Sized,
+            {
+                let s: &str = MsgUnpack::unpack_with_ctx(bytes, ctx)?;
+                let mut string = String::new();
+                string.push_str(s).map_err(|_| UnpackErr::BufferOverflow)?;
+                Ok(string)
+            }
+        }
+
+        impl<K, V, const N: usize> MsgPack for LinearMap<K, V, N>
+        where
+            K: MsgPack + Eq,
+            V: MsgPack,
+        {
+            fn pack(&self) -> impl Iterator<Item = Piece<'_>> {
+                pack_map(self.len(), self.iter())
+            }
+        }
+
+        impl<'buf, K, V, const N: usize> MsgUnpack<'buf> for LinearMap<K, V, N>
+        where
+            K: MsgUnpack<'buf> + Eq,
+            V: MsgUnpack<'buf>,
+        {
+            fn unpack(bytes: &mut &'buf [u8]) -> Result<Self, UnpackErr>
+            where
+                Self: Sized,
+            {
+                Self::unpack_with_ctx(bytes, &mut UnpackCtx::new())
+            }
+
+            fn unpack_with_ctx(
+                bytes: &mut &'buf [u8],
+                ctx: &mut UnpackCtx,
+            ) -> Result<Self, UnpackErr>
+            where
+                Self: Sized,
+            {
+                unpack_map_with(bytes, ctx, LinearMap::new(), |map, k, v| {
+                    map.insert(k, v).map(|_| ())
+                })
+            }
+        }
+
+        impl<T, const N: usize> MsgPack for Deque<T, N>
+        where
+            T: MsgPack,
+        {
+            fn pack(&self) -> impl Iterator<Item = Piece<'_>> {
+                pack_array(self.len(), self.iter())
+            }
+        }
+
+        impl<'buf, T, const N: usize> MsgUnpack<'buf> for Deque<T, N>
+        where
+            T: MsgUnpack<'buf>,
+        {
+            fn unpack(bytes: &mut &'buf [u8]) -> Result<Self, UnpackErr>
+            where
+                Self: Sized,
+            {
+                Self::unpack_with_ctx(bytes, &mut UnpackCtx::new())
+            }
+
+            fn unpack_with_ctx(
+                bytes: &mut &'buf [u8],
+                ctx: &mut UnpackCtx,
+            ) -> Result<Self, UnpackErr>
+            where
+                Self: Sized,
+            {
+                unpack_array_with(bytes, ctx, Deque::new(), Deque::push_back)
+            }
+        }
+
+        impl<K, V, S, const N: usize> MsgPack for IndexMap<K, V, S, N>
+        where
+            K: MsgPack + Eq + $Hash,
+            V: MsgPack,
+            S: $BuildHasher,
+        {
+            fn pack(&self) -> impl Iterator<Item = Piece<'_>> {
+                pack_map(self.len(), self.iter())
+            }
+        }
+
+        impl<'buf, K, V, S, const N: usize> MsgUnpack<'buf> for IndexMap<K, V, S, N>
+        where
+            K: MsgUnpack<'buf> + Eq + $Hash,
+            V: MsgUnpack<'buf>,
+            S: $BuildHasher + Default,
+        {
+            fn unpack(bytes: &mut &'buf [u8]) -> Result<Self, UnpackErr>
+            where
+                Self: Sized,
+            {
+                Self::unpack_with_ctx(bytes, &mut UnpackCtx::new())
+            }
+
+            fn unpack_with_ctx(
+                bytes: &mut &'buf [u8],
+                ctx: &mut UnpackCtx,
+            ) -> Result<Self, UnpackErr>
+            where
+                Self: Sized,
+            {
+                unpack_map_with(bytes, ctx, IndexMap::default(), |map, k, v| {
+                    map.insert(k, v).map(|_| ())
+                })
+            }
+        }
+
+        impl<T, S, const N: usize> MsgPack for IndexSet<T, S, N>
+        where
+            T: MsgPack + Eq + $Hash,
+            S: $BuildHasher,
+        {
+            fn pack(&self) -> impl Iterator<Item = Piece<'_>> {
+                pack_array(self.len(), self.iter())
+            }
+        }
+
+        impl<'buf, T, S, const N: usize> MsgUnpack<'buf> for IndexSet<T, S, N>
+        where
+            T: MsgUnpack<'buf> + Eq + $Hash,
+            S: $BuildHasher + Default,
+        {
+            fn unpack(bytes: &mut &'buf [u8]) -> Result<Self, UnpackErr>
+            where
+                Self: Sized,
+            {
+                Self::unpack_with_ctx(bytes, &mut UnpackCtx::new())
+            }
+
+            fn unpack_with_ctx(
+                bytes: &mut &'buf [u8],
+                ctx: &mut UnpackCtx,
+            ) -> Result<Self, UnpackErr>
+            where
+                Self: Sized,
+            {
+                unpack_array_with(bytes, ctx, IndexSet::default(), |set, elem| {
+                    set.insert(elem).map(|_| ())
+                })
+            }
+        }
+
+        /// Binary heaps are packed as an array, in the same order as [BinaryHeap::iter].
+        impl<T, K, const N: usize> MsgPack for BinaryHeap<T, K, N>
+        where
+            T: MsgPack + Ord,
+            K: Kind,
+        {
+            fn pack(&self) -> impl Iterator<Item = Piece<'_>> {
+                pack_array(self.len(), self.iter())
+            }
+        }
+
+        impl<'buf, T, K, const N: usize> MsgUnpack<'buf> for BinaryHeap<T, K, N>
+        where
+            T: MsgUnpack<'buf> + Ord,
+            K: Kind,
+        {
+            fn unpack(bytes: &mut &'buf [u8]) -> Result<Self, UnpackErr>
+            where
+                Self: Sized,
+            {
+                Self::unpack_with_ctx(bytes, &mut UnpackCtx::new())
+            }
+
+            fn unpack_with_ctx(
+                bytes: &mut &'buf [u8],
+                ctx: &mut UnpackCtx,
+            ) -> Result<Self, UnpackErr>
+            where
+                Self: Sized,
+            {
+                unpack_array_with(bytes, ctx, BinaryHeap::new(), BinaryHeap::push)
+            }
+        }
+
+        /// History buffers are packed as an array, from the oldest to the newest element.
+        impl<T, const N: usize> MsgPack for $HistoryBuffer<T, N>
+        where
+            T: MsgPack,
+        {
+            fn pack(&self) -> impl Iterator<Item = Piece<'_>> {
+                pack_array(self.len(), self.oldest_ordered())
+            }
+        }
+
+        /// Unpacking an array with more than `N` elements returns [UnpackErr::BufferOverflow],
+        /// instead of only keeping the newest elements.
+        impl<'buf, T, const N: usize> MsgUnpack<'buf> for $HistoryBuffer<T, N>
+        where
+            T: MsgUnpack<'buf>,
+        {
+            fn unpack(bytes: &mut &'buf [u8]) -> Result<Self, UnpackErr>
+            where
+                Self: Sized,
+            {
+                Self::unpack_with_ctx(bytes, &mut UnpackCtx::new())
+            }
+
+            fn unpack_with_ctx(
+                bytes: &mut &'buf [u8],
+                ctx: &mut UnpackCtx,
+            ) -> Result<Self, UnpackErr>
+            where
+                Self: Sized,
+            {
+                unpack_array_with(bytes, ctx, $HistoryBuffer::new(), |buf, elem| {
+                    if buf.len() == N {
+                        return Err(elem);
+                    }
+                    buf.write(elem);
+                    Ok(())
+                })
+            }
+        }
+
+        /// A `heapless::Vec<u8, N>` which is packed as bin, instead of as an array of integers.
+        ///
+        /// It may be unpacked from either a bin or an array, for compatibility with data that was
+        /// packed as a `heapless::Vec<u8, N>`. See also `#[msgpck(bytes)]`, which does the same
+        /// for a field.
+        #[derive(Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+        pub struct ByteBuf<const N: usize>(pub Vec<u8, N>);
+
+        impl<'buf, const N: usize> UnpackBytes<'buf> for Vec<u8, N> {
+            fn from_bin(bin: &'buf [u8]) -> Result<Self, UnpackErr> {
+                Vec::from_slice(bin).map_err(|_| UnpackErr::BufferOverflow)
+            }
+
+            fn unpack_array(
+                bytes: &mut &'buf [u8],
+                ctx: &mut UnpackCtx,
+            ) -> Result<Self, UnpackErr> {
+                let len = unpack_array_header_with_ctx(bytes, ctx)?;
+                if len > N {
+                    return Err(UnpackErr::BufferOverflow);
+                }
+
+                let mut vec = Vec::new();
+                for _ in 0..len {
+                    let _ = vec.push(u8::unpack_with_ctx(bytes, ctx)?);
+                }
+                Ok(vec)
+            }
+        }
+
+        impl<const N: usize> MsgPack for ByteBuf<N> {
+            fn pack(&self) -> impl Iterator<Item = Piece<'_>> {
+                self.0.as_slice().pack()
+            }
+
+            fn pack_with_writer(&self, w: &mut dyn Write) -> Result<usize, PackErr> {
+                self.0.as_slice().pack_with_writer(w)
+            }
+        }
+
+        impl<'buf, const N: usize> MsgUnpack<'buf> for ByteBuf<N> {
+            fn unpack(bytes: &mut &'buf [u8]) -> Result<Self, UnpackErr>
+            where
+                Self: Sized,
+            {
+                Self::unpack_with_ctx(bytes, &mut UnpackCtx::new())
+            }
+
+            fn unpack_with_ctx(
+                bytes: &mut &'buf [u8],
+                ctx: &mut UnpackCtx,
+            ) -> Result<Self, UnpackErr>
+            where
+                Self: Sized,
+            {
+                unpack_bytes(bytes, ctx).map(ByteBuf)
+            }
+        }
+
+        impl<const N: usize> From<Vec<u8, N>> for ByteBuf<N> {
+            fn from(bytes: Vec<u8, N>) -> Self {
+                ByteBuf(bytes)
+            }
+        }
+
+        impl<const N: usize> Deref for ByteBuf<N> {
+            type Target = Vec<u8, N>;
+
+            fn deref(&self) -> &Vec<u8, N> {
+                &self.0
+            }
+        }
+
+        impl<const N: usize> DerefMut for ByteBuf<N> {
+            fn deref_mut(&mut self) -> &mut Vec<u8, N> {
+                &mut self.0
+            }
+        }
+    };
+}
+
+pub(crate) use impl_heapless;
@@ -0,0 +1,6 @@
+super::heapless::impl_heapless! {
+    heapless: heapless09,
+    history_buffer: HistoryBuf,
+    hash: core::hash::Hash,
+    build_hasher: core::hash::BuildHasher,
+}
@@ -0,0 +1,75 @@
+use core::hash::{BuildHasher, Hash};
+use indexmap::{IndexMap, IndexSet};
+
+use crate::{
+    util::{pack_array, pack_map, unpack_array, unpack_map},
+    MsgPack, MsgUnpack, Piece, UnpackCtx,
+};
+
+/// Index maps are packed in their iteration order, which is preserved when unpacking.
+impl<K, V, S> MsgPack for IndexMap<K, V, S>
+where
+    K: MsgPack,
+    V: MsgPack,
+{
+    fn pack(&self) -> impl Iterator<Item = Piece<'_>> {
+        pack_map(self.len(), self.iter())
+    }
+}
+
+impl<'buf, K, V, S> MsgUnpack<'buf> for IndexMap<K, V, S>
+where
+    K: MsgUnpack<'buf> + Hash + Eq,
+    V: MsgUnpack<'buf>,
+    S: BuildHasher + Default,
+{
+    fn unpack(bytes: &mut &'buf [u8]) -> Result<Self, crate::UnpackErr>
+    where
+        Self: Sized,
+    {
+        Self::unpack_with_ctx(bytes, &mut UnpackCtx::new())
+    }
+
+    fn unpack_with_ctx(
+        bytes: &mut &'buf [u8],
+        ctx: &mut UnpackCtx,
+    ) -> Result<Self, crate::UnpackErr>
+    where
+        Self: Sized,
+    {
+        unpack_map(bytes, ctx)
+    }
+}
+
+/// Index sets are packed in their iteration order, which is preserved when unpacking.
+impl<T, S> MsgPack for IndexSet<T, S>
+where
+    T: MsgPack,
+{
+    fn pack(&self) -> impl Iterator<Item = Piece<'_>> {
+        pack_array(self.len(), self.iter())
+    }
+}
+
+impl<'buf, T, S> MsgUnpack<'buf> for IndexSet<T, S>
+where
+    T: MsgUnpack<'buf> + Hash + Eq,
+    S: BuildHasher + Default,
+{
+    fn unpack(bytes: &mut &'buf [u8]) -> Result<Self, crate::UnpackErr>
+    where
+        Self: Sized,
+    {
+        Self::unpack_with_ctx(bytes, &mut UnpackCtx::new())
+    }
+
+    fn unpack_with_ctx(
+        bytes: &mut &'buf [u8],
+        ctx: &mut UnpackCtx,
+    ) -> Result<Self, crate::UnpackErr>
+    where
+        Self: Sized,
+    {
+        unpack_array(bytes, ctx)
+    }
+}
@@ -1,5 +1,10 @@
-use super::uints::pack_u64;
-use crate::{marker::Marker, piece::Pair, util::slice_take, MsgPack, MsgUnpack, Piece, UnpackErr};
+use super::uints::{add_bin_kind, pack_int128, pack_u64, unpack_int128_bin, unpack_u64};
+use crate::{
+    marker::{ExpectedKind, Marker},
+    piece::Pair,
+    util::slice_take,
+    MsgPack, MsgUnpack, Piece, UnpackCtx, UnpackErr,
+};
 
 impl MsgPack for i8 {
     fn pack(&self) -> impl Iterator<Item = Piece<'_>> {
@@ -31,12 +36,26 @@ impl MsgPack for isize {
     }
 }
 
+/// 128-bit integers are packed as a bin of 16 big-endian bytes, like in rmp_serde.
+impl MsgPack for i128 {
+    fn pack(&self) -> impl Iterator<Item = Piece<'_>> {
+        pack_int128(self.to_be_bytes())
+    }
+}
+
 impl<'buf> MsgUnpack<'buf> for i8 {
     fn unpack(bytes: &mut &'buf [u8]) -> Result<Self, UnpackErr>
     where
         Self: Sized,
     {
-        let n = unpack_i64(bytes)?;
+        Self::unpack_with_ctx(bytes, &mut UnpackCtx::new())
+    }
+
+    fn unpack_with_ctx(bytes: &mut &'buf [u8], ctx: &mut UnpackCtx) -> Result<Self, UnpackErr>
+    where
+        Self: Sized,
+    {
+        let n = unpack_i64(bytes, ctx)?;
         Ok(n.try_into()?)
     }
 }
@@ -46,7 +65,14 @@ impl<'buf> MsgUnpack<'buf> for i16 {
     where
         Self: Sized,
     {
-        let n = unpack_i64(bytes)?;
+        Self::unpack_with_ctx(bytes, &mut UnpackCtx::new())
+    }
+
+    fn unpack_with_ctx(bytes: &mut &'buf [u8], ctx: &mut UnpackCtx) -> Result<Self, UnpackErr>
+    where
+        Self: Sized,
+    {
+        let n = unpack_i64(bytes, ctx)?;
         Ok(n.try_into()?)
     }
 }
@@ -56,7 +82,14 @@ impl<'buf> MsgUnpack<'buf> for i32 {
     where
         Self: Sized,
     {
-        let n = unpack_i64(bytes)?;
+        Self::unpack_with_ctx(bytes, &mut UnpackCtx::new())
+    }
+
+    fn unpack_with_ctx(bytes: &mut &'buf [u8], ctx: &mut UnpackCtx) -> Result<Self, UnpackErr>
+    where
+        Self: Sized,
+    {
+        let n = unpack_i64(bytes, ctx)?;
         Ok(n.try_into()?)
     }
 }
@@ -66,7 +99,14 @@ impl<'buf> MsgUnpack<'buf> for i64 {
     where
         Self: Sized,
     {
-        unpack_i64(bytes)
+        Self::unpack_with_ctx(bytes, &mut UnpackCtx::new())
+    }
+
+    fn unpack_with_ctx(bytes: &mut &'buf [u8], ctx: &mut UnpackCtx) -> Result<Self, UnpackErr>
+    where
+        Self: Sized,
+    {
+        unpack_i64(bytes, ctx)
     }
 }
 
@@ -75,14 +115,49 @@ impl<'buf> MsgUnpack<'buf> for isize {
     where
         Self: Sized,
     {
-        let n = unpack_i64(bytes)?;
+        Self::unpack_with_ctx(bytes, &mut UnpackCtx::new())
+    }
+
+    fn unpack_with_ctx(bytes: &mut &'buf [u8], ctx: &mut UnpackCtx) -> Result<Self, UnpackErr>
+    where
+        Self: Sized,
+    {
+        let n = unpack_i64(bytes, ctx)?;
         Ok(n.try_into()?)
     }
 }
 
-pub fn unpack_i64(bytes: &mut &[u8]) -> Result<i64, UnpackErr> {
+/// A 128-bit integer may also be unpacked from any integer that fits, except in
+/// [UnpackMode::Canonical](crate::UnpackMode::Canonical) mode.
+impl<'buf> MsgUnpack<'buf> for i128 {
+    fn unpack(bytes: &mut &'buf [u8]) -> Result<Self, UnpackErr>
+    where
+        Self: Sized,
+    {
+        Self::unpack_with_ctx(bytes, &mut UnpackCtx::new())
+    }
+
+    fn unpack_with_ctx(bytes: &mut &'buf [u8], ctx: &mut UnpackCtx) -> Result<Self, UnpackErr>
+    where
+        Self: Sized,
+    {
+        if let Some(be_bytes) = unpack_int128_bin(bytes, ctx)? {
+            return Ok(i128::from_be_bytes(be_bytes));
+        }
+
+        // u64 values don't all fit in an i64
+        match bytes.first().map(|&b| ExpectedKind::of(Marker::from_u8(b))) {
+            Some(ExpectedKind::UINT) => Ok(unpack_u64(bytes, ctx)?.into()),
+            _ => Ok(unpack_i64(bytes, ctx).map_err(add_bin_kind)?.into()),
+        }
+    }
+}
+
+pub fn unpack_i64(bytes: &mut &[u8], ctx: &UnpackCtx) -> Result<i64, UnpackErr> {
     let &[b] = slice_take(bytes)?;
-    Ok(match Marker::from_u8(b) {
+    let marker = Marker::from_u8(b);
+
+    let i = match marker {
         Marker::FixNeg(i) => i.into(),
         Marker::FixPos(n) => n.into(),
         Marker::I8 => {
@@ -102,8 +177,17 @@ pub fn unpack_i64(bytes: &mut &[u8]) -> Result<i64, UnpackErr> {
             let n = u64::from_be_bytes(*slice_take(bytes)?);
             n.try_into().map_err(UnpackErr::IntTooBig)?
         }
-        m => return Err(UnpackErr::WrongMarker(m)),
-    })
+        m => {
+            return Err(UnpackErr::wrong_marker(
+                m,
+                ExpectedKind::UINT | ExpectedKind::INT,
+            ))
+        }
+    };
+
+    crate::util::check_canonical(ctx, marker, pack_i64(i).marker())?;
+
+    Ok(i)
 }
 
 pub const fn pack_i64<'a>(i: i64) -> Pair<'a> {
@@ -124,6 +208,6 @@ pub const fn pack_i64<'a>(i: i64) -> Pair<'a> {
         ),
         ..=-1 => Pair(Piece::from_marker(Marker::FixNeg(i as i8)), None),
         // If the value is positive, pack as an unsigned integer.
-        _ => return pack_u64(i as u64),
+        _ => pack_u64(i as u64),
     }
 }
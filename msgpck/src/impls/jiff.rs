@@ -0,0 +1,50 @@
+use crate::{
+    timestamp::{pack_timestamp, unpack_timestamp_or_str, TimestampOrStr},
+    ConversionErr, MsgPack, MsgUnpack, Piece, Timestamp, UnpackCtx, UnpackErr,
+};
+
+/// Timestamps are packed using the msgpack timestamp extension type.
+///
+/// They may also be unpacked from an integer number of seconds since the Unix epoch, or from an
+/// RFC 3339 string like `2023-11-14T22:13:20Z`, except in
+/// [UnpackMode::Canonical](crate::UnpackMode::Canonical) mode.
+impl MsgPack for jiff::Timestamp {
+    fn pack(&self) -> impl Iterator<Item = Piece<'_>> {
+        let mut seconds = self.as_second();
+        let mut nanoseconds = self.subsec_nanosecond();
+
+        // jiff uses negative nanoseconds for timestamps before the Unix epoch
+        if nanoseconds < 0 {
+            seconds -= 1;
+            nanoseconds += 1_000_000_000;
+        }
+
+        let ts = Timestamp::new(seconds, nanoseconds as u32)
+            .expect("nanoseconds are less than a second");
+        pack_timestamp(ts)
+    }
+}
+
+impl<'buf> MsgUnpack<'buf> for jiff::Timestamp {
+    fn unpack(bytes: &mut &'buf [u8]) -> Result<Self, UnpackErr>
+    where
+        Self: Sized,
+    {
+        Self::unpack_with_ctx(bytes, &mut UnpackCtx::new())
+    }
+
+    fn unpack_with_ctx(bytes: &mut &'buf [u8], ctx: &mut UnpackCtx) -> Result<Self, UnpackErr>
+    where
+        Self: Sized,
+    {
+        match unpack_timestamp_or_str(bytes, ctx)? {
+            TimestampOrStr::Timestamp(ts) => {
+                jiff::Timestamp::new(ts.seconds(), ts.nanoseconds() as i32)
+                    .map_err(|_| UnpackErr::Other("timestamp out of range for jiff::Timestamp"))
+            }
+            TimestampOrStr::Str(s) => s
+                .parse()
+                .map_err(|e: jiff::Error| UnpackErr::Conversion(ConversionErr::new(e))),
+        }
+    }
+}
@@ -1,20 +1,70 @@
+pub mod arrays;
 pub mod bool;
 pub mod borrow;
 pub mod bytes;
+pub mod duration;
 pub mod floats;
 pub mod ints;
+pub mod num;
+pub mod ops;
 pub mod option;
 pub mod strings;
+pub mod tuples;
 pub mod uints;
 
 #[cfg(feature = "alloc")]
 pub mod alloc;
 
+#[cfg(feature = "std")]
+pub mod net;
+
+#[cfg(feature = "std")]
+pub mod path;
+
+#[cfg(feature = "std")]
+pub mod system_time;
+
+#[cfg(any(feature = "std", feature = "hashbrown"))]
+mod hash;
+
 #[cfg(feature = "std")]
 pub mod std;
 
+#[cfg(feature = "hashbrown")]
+pub mod hashbrown;
+
+#[cfg(feature = "indexmap")]
+pub mod indexmap;
+
+#[cfg(feature = "uuid")]
+pub mod uuid;
+
+#[cfg(feature = "chrono")]
+pub mod chrono;
+
+#[cfg(feature = "time")]
+pub mod time03;
+
+#[cfg(feature = "jiff")]
+pub mod jiff;
+
+#[cfg(feature = "arrayvec")]
+pub mod arrayvec;
+
+#[cfg(feature = "smallvec")]
+pub mod smallvec;
+
+#[cfg(feature = "tinyvec")]
+pub mod tinyvec;
+
+#[cfg(any(feature = "heapless07", feature = "heapless08", feature = "heapless09"))]
+mod heapless;
+
 #[cfg(feature = "heapless07")]
 pub mod heapless07;
 
 #[cfg(feature = "heapless08")]
 pub mod heapless08;
+
+#[cfg(feature = "heapless09")]
+pub mod heapless09;
@@ -0,0 +1,224 @@
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6};
+
+use super::uints::pack_u64;
+use crate::{
+    enums::{pack_enum_header, unpack_enum_header_with_ctx, EnumHeader, Variant},
+    util::{pack_array_header, Either},
+    MsgPack, MsgUnpack, PathSegment, Piece, UnpackCtx, UnpackErr,
+};
+
+/// IPv4 addresses are packed as an array of their 4 octets, like in rmp_serde.
+impl MsgPack for Ipv4Addr {
+    fn pack(&self) -> impl Iterator<Item = Piece<'_>> {
+        pack_octets(self.octets())
+    }
+}
+
+impl<'buf> MsgUnpack<'buf> for Ipv4Addr {
+    fn unpack(bytes: &mut &'buf [u8]) -> Result<Self, UnpackErr>
+    where
+        Self: Sized,
+    {
+        Self::unpack_with_ctx(bytes, &mut UnpackCtx::new())
+    }
+
+    fn unpack_with_ctx(bytes: &mut &'buf [u8], ctx: &mut UnpackCtx) -> Result<Self, UnpackErr>
+    where
+        Self: Sized,
+    {
+        <[u8; 4]>::unpack_with_ctx(bytes, ctx).map(Ipv4Addr::from)
+    }
+}
+
+/// IPv6 addresses are packed as an array of their 16 octets, like in rmp_serde.
+impl MsgPack for Ipv6Addr {
+    fn pack(&self) -> impl Iterator<Item = Piece<'_>> {
+        pack_octets(self.octets())
+    }
+}
+
+impl<'buf> MsgUnpack<'buf> for Ipv6Addr {
+    fn unpack(bytes: &mut &'buf [u8]) -> Result<Self, UnpackErr>
+    where
+        Self: Sized,
+    {
+        Self::unpack_with_ctx(bytes, &mut UnpackCtx::new())
+    }
+
+    fn unpack_with_ctx(bytes: &mut &'buf [u8], ctx: &mut UnpackCtx) -> Result<Self, UnpackErr>
+    where
+        Self: Sized,
+    {
+        <[u8; 16]>::unpack_with_ctx(bytes, ctx).map(Ipv6Addr::from)
+    }
+}
+
+/// IP addresses are packed as an enum with the variants `V4` and `V6`, like in rmp_serde.
+impl MsgPack for IpAddr {
+    fn pack(&self) -> impl Iterator<Item = Piece<'_>> {
+        match self {
+            IpAddr::V4(ip) => Either::A(pack_variant_header("V4").chain(ip.pack())),
+            IpAddr::V6(ip) => Either::B(pack_variant_header("V6").chain(ip.pack())),
+        }
+    }
+}
+
+impl<'buf> MsgUnpack<'buf> for IpAddr {
+    fn unpack(bytes: &mut &'buf [u8]) -> Result<Self, UnpackErr>
+    where
+        Self: Sized,
+    {
+        Self::unpack_with_ctx(bytes, &mut UnpackCtx::new())
+    }
+
+    fn unpack_with_ctx(bytes: &mut &'buf [u8], ctx: &mut UnpackCtx) -> Result<Self, UnpackErr>
+    where
+        Self: Sized,
+    {
+        match unpack_variant_header(bytes, ctx)? {
+            AddrVariant::V4 => {
+                let start = *bytes;
+                Ok(IpAddr::V4(MsgUnpack::unpack_with_ctx(bytes, ctx).map_err(
+                    |e| ctx.error_at(PathSegment::Variant("V4"), start, e),
+                )?))
+            }
+            AddrVariant::V6 => {
+                let start = *bytes;
+                Ok(IpAddr::V6(MsgUnpack::unpack_with_ctx(bytes, ctx).map_err(
+                    |e| ctx.error_at(PathSegment::Variant("V6"), start, e),
+                )?))
+            }
+        }
+    }
+}
+
+/// IPv4 socket addresses are packed as an array of `[ip, port]`, like in rmp_serde.
+impl MsgPack for SocketAddrV4 {
+    fn pack(&self) -> impl Iterator<Item = Piece<'_>> {
+        pack_array_header(2)
+            .chain(self.ip().pack())
+            .chain(pack_u64(self.port().into()).pieces())
+    }
+}
+
+impl<'buf> MsgUnpack<'buf> for SocketAddrV4 {
+    fn unpack(bytes: &mut &'buf [u8]) -> Result<Self, UnpackErr>
+    where
+        Self: Sized,
+    {
+        Self::unpack_with_ctx(bytes, &mut UnpackCtx::new())
+    }
+
+    fn unpack_with_ctx(bytes: &mut &'buf [u8], ctx: &mut UnpackCtx) -> Result<Self, UnpackErr>
+    where
+        Self: Sized,
+    {
+        let (ip, port) = MsgUnpack::unpack_with_ctx(bytes, ctx)?;
+        Ok(SocketAddrV4::new(ip, port))
+    }
+}
+
+/// IPv6 socket addresses are packed as an array of `[ip, port]`, like in rmp_serde.
+///
+/// **NOTE**: The flow info and scope ID are not packed, and are zero when unpacked.
+impl MsgPack for SocketAddrV6 {
+    fn pack(&self) -> impl Iterator<Item = Piece<'_>> {
+        pack_array_header(2)
+            .chain(self.ip().pack())
+            .chain(pack_u64(self.port().into()).pieces())
+    }
+}
+
+impl<'buf> MsgUnpack<'buf> for SocketAddrV6 {
+    fn unpack(bytes: &mut &'buf [u8]) -> Result<Self, UnpackErr>
+    where
+        Self: Sized,
+    {
+        Self::unpack_with_ctx(bytes, &mut UnpackCtx::new())
+    }
+
+    fn unpack_with_ctx(bytes: &mut &'buf [u8], ctx: &mut UnpackCtx) -> Result<Self, UnpackErr>
+    where
+        Self: Sized,
+    {
+        let (ip, port) = MsgUnpack::unpack_with_ctx(bytes, ctx)?;
+        Ok(SocketAddrV6::new(ip, port, 0, 0))
+    }
+}
+
+/// Socket addresses are packed as an enum with the variants `V4` and `V6`, like in rmp_serde.
+impl MsgPack for SocketAddr {
+    fn pack(&self) -> impl Iterator<Item = Piece<'_>> {
+        match self {
+            SocketAddr::V4(addr) => Either::A(pack_variant_header("V4").chain(addr.pack())),
+            SocketAddr::V6(addr) => Either::B(pack_variant_header("V6").chain(addr.pack())),
+        }
+    }
+}
+
+impl<'buf> MsgUnpack<'buf> for SocketAddr {
+    fn unpack(bytes: &mut &'buf [u8]) -> Result<Self, UnpackErr>
+    where
+        Self: Sized,
+    {
+        Self::unpack_with_ctx(bytes, &mut UnpackCtx::new())
+    }
+
+    fn unpack_with_ctx(bytes: &mut &'buf [u8], ctx: &mut UnpackCtx) -> Result<Self, UnpackErr>
+    where
+        Self: Sized,
+    {
+        match unpack_variant_header(bytes, ctx)? {
+            AddrVariant::V4 => {
+                let start = *bytes;
+                Ok(SocketAddr::V4(
+                    MsgUnpack::unpack_with_ctx(bytes, ctx)
+                        .map_err(|e| ctx.error_at(PathSegment::Variant("V4"), start, e))?,
+                ))
+            }
+            AddrVariant::V6 => {
+                let start = *bytes;
+                Ok(SocketAddr::V6(
+                    MsgUnpack::unpack_with_ctx(bytes, ctx)
+                        .map_err(|e| ctx.error_at(PathSegment::Variant("V6"), start, e))?,
+                ))
+            }
+        }
+    }
+}
+
+fn pack_octets<'a, const N: usize>(octets: [u8; N]) -> impl Iterator<Item = Piece<'a>> {
+    pack_array_header(N).chain(
+        octets
+            .into_iter()
+            .flat_map(|octet| pack_u64(octet.into()).pieces()),
+    )
+}
+
+fn pack_variant_header<'a>(name: &'static str) -> impl Iterator<Item = Piece<'a>> {
+    pack_enum_header(EnumHeader {
+        variant: Variant::Name(name),
+        unit: false,
+    })
+}
+
+/// The variants of [IpAddr] and [SocketAddr].
+enum AddrVariant {
+    V4,
+    V6,
+}
+
+/// Unpack the header of an [IpAddr] or [SocketAddr], which may use either the variant name or
+/// its index.
+fn unpack_variant_header(bytes: &mut &[u8], ctx: &mut UnpackCtx) -> Result<AddrVariant, UnpackErr> {
+    let header = unpack_enum_header_with_ctx(bytes, ctx)?;
+    if header.unit {
+        return Err(UnpackErr::UnexpectedUnitVariant);
+    }
+
+    match header.variant {
+        Variant::Name("V4") | Variant::Discriminant(0) => Ok(AddrVariant::V4),
+        Variant::Name("V6") | Variant::Discriminant(1) => Ok(AddrVariant::V6),
+        _ => Err(UnpackErr::UnknownVariant),
+    }
+}
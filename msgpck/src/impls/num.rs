@@ -0,0 +1,111 @@
+use core::num::{
+    NonZeroI128, NonZeroI16, NonZeroI32, NonZeroI64, NonZeroI8, NonZeroU128, NonZeroU16,
+    NonZeroU32, NonZeroU64, NonZeroU8, Saturating, Wrapping,
+};
+
+use super::{
+    ints::pack_i64,
+    uints::{pack_int128, pack_u64},
+};
+use crate::{MsgPack, MsgUnpack, PackErr, Piece, UnpackCtx, UnpackErr, Write};
+
+/// Implement [MsgPack] and [MsgUnpack] for a NonZero integer, which is packed like the integer.
+macro_rules! impl_non_zero {
+    ($($non_zero:ty => $int:ty: |$n:ident| $pack:expr),* $(,)?) => {$(
+        impl MsgPack for $non_zero {
+            fn pack(&self) -> impl Iterator<Item = Piece<'_>> {
+                let $n = self.get();
+                $pack
+            }
+        }
+
+        impl<'buf> MsgUnpack<'buf> for $non_zero {
+            fn unpack(bytes: &mut &'buf [u8]) -> Result<Self, UnpackErr>
+            where
+                Self: Sized,
+            {
+                Self::unpack_with_ctx(bytes, &mut UnpackCtx::new())
+            }
+
+            fn unpack_with_ctx(
+                bytes: &mut &'buf [u8],
+                ctx: &mut UnpackCtx,
+            ) -> Result<Self, UnpackErr>
+            where
+                Self: Sized,
+            {
+                <$non_zero>::new(<$int>::unpack_with_ctx(bytes, ctx)?)
+                    .ok_or(UnpackErr::UnexpectedZero)
+            }
+        }
+    )*};
+}
+
+impl_non_zero!(
+    NonZeroU8 => u8: |n| pack_u64(n.into()).pieces(),
+    NonZeroU16 => u16: |n| pack_u64(n.into()).pieces(),
+    NonZeroU32 => u32: |n| pack_u64(n.into()).pieces(),
+    NonZeroU64 => u64: |n| pack_u64(n).pieces(),
+    NonZeroU128 => u128: |n| pack_int128(n.to_be_bytes()),
+    NonZeroI8 => i8: |n| pack_i64(n.into()).pieces(),
+    NonZeroI16 => i16: |n| pack_i64(n.into()).pieces(),
+    NonZeroI32 => i32: |n| pack_i64(n.into()).pieces(),
+    NonZeroI64 => i64: |n| pack_i64(n).pieces(),
+    NonZeroI128 => i128: |n| pack_int128(n.to_be_bytes()),
+);
+
+impl<T: MsgPack> MsgPack for Wrapping<T> {
+    #[inline(always)]
+    fn pack(&self) -> impl Iterator<Item = Piece<'_>> {
+        self.0.pack()
+    }
+
+    fn pack_with_writer(&self, w: &mut dyn Write) -> Result<usize, PackErr> {
+        self.0.pack_with_writer(w)
+    }
+}
+
+impl<'buf, T: MsgUnpack<'buf>> MsgUnpack<'buf> for Wrapping<T> {
+    #[inline(always)]
+    fn unpack(bytes: &mut &'buf [u8]) -> Result<Self, UnpackErr>
+    where
+        Self: Sized,
+    {
+        Self::unpack_with_ctx(bytes, &mut UnpackCtx::new())
+    }
+
+    fn unpack_with_ctx(bytes: &mut &'buf [u8], ctx: &mut UnpackCtx) -> Result<Self, UnpackErr>
+    where
+        Self: Sized,
+    {
+        T::unpack_with_ctx(bytes, ctx).map(Wrapping)
+    }
+}
+
+impl<T: MsgPack> MsgPack for Saturating<T> {
+    #[inline(always)]
+    fn pack(&self) -> impl Iterator<Item = Piece<'_>> {
+        self.0.pack()
+    }
+
+    fn pack_with_writer(&self, w: &mut dyn Write) -> Result<usize, PackErr> {
+        self.0.pack_with_writer(w)
+    }
+}
+
+impl<'buf, T: MsgUnpack<'buf>> MsgUnpack<'buf> for Saturating<T> {
+    #[inline(always)]
+    fn unpack(bytes: &mut &'buf [u8]) -> Result<Self, UnpackErr>
+    where
+        Self: Sized,
+    {
+        Self::unpack_with_ctx(bytes, &mut UnpackCtx::new())
+    }
+
+    fn unpack_with_ctx(bytes: &mut &'buf [u8], ctx: &mut UnpackCtx) -> Result<Self, UnpackErr>
+    where
+        Self: Sized,
+    {
+        T::unpack_with_ctx(bytes, ctx).map(Saturating)
+    }
+}
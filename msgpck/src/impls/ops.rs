@@ -0,0 +1,156 @@
+use core::{
+    marker::PhantomData,
+    ops::{Range, RangeFrom, RangeInclusive, RangeTo},
+};
+
+use super::arrays::check_len;
+use crate::{
+    util::{pack_array_header, unpack_array_header_with_ctx, write_pieces},
+    MsgPack, MsgUnpack, PackErr, Piece, UnpackCtx, UnpackErr, Write,
+};
+
+/// Ranges are packed as an array of `[start, end]`, like in rmp_serde.
+impl<T: MsgPack> MsgPack for Range<T> {
+    fn pack(&self) -> impl Iterator<Item = Piece<'_>> {
+        pack_array_header(2)
+            .chain(self.start.pack())
+            .chain(self.end.pack())
+    }
+
+    fn pack_with_writer(&self, w: &mut dyn Write) -> Result<usize, PackErr> {
+        let mut n = write_pieces(pack_array_header(2), w)?;
+        n += self.start.pack_with_writer(w)?;
+        n += self.end.pack_with_writer(w)?;
+        Ok(n)
+    }
+}
+
+impl<'buf, T: MsgUnpack<'buf>> MsgUnpack<'buf> for Range<T> {
+    fn unpack(bytes: &mut &'buf [u8]) -> Result<Self, UnpackErr>
+    where
+        Self: Sized,
+    {
+        Self::unpack_with_ctx(bytes, &mut UnpackCtx::new())
+    }
+
+    fn unpack_with_ctx(bytes: &mut &'buf [u8], ctx: &mut UnpackCtx) -> Result<Self, UnpackErr>
+    where
+        Self: Sized,
+    {
+        let [start, end] = <[T; 2]>::unpack_with_ctx(bytes, ctx)?;
+        Ok(start..end)
+    }
+}
+
+/// Inclusive ranges are packed as an array of `[start, end]`, like in rmp_serde.
+impl<T: MsgPack> MsgPack for RangeInclusive<T> {
+    fn pack(&self) -> impl Iterator<Item = Piece<'_>> {
+        pack_array_header(2)
+            .chain(self.start().pack())
+            .chain(self.end().pack())
+    }
+
+    fn pack_with_writer(&self, w: &mut dyn Write) -> Result<usize, PackErr> {
+        let mut n = write_pieces(pack_array_header(2), w)?;
+        n += self.start().pack_with_writer(w)?;
+        n += self.end().pack_with_writer(w)?;
+        Ok(n)
+    }
+}
+
+impl<'buf, T: MsgUnpack<'buf>> MsgUnpack<'buf> for RangeInclusive<T> {
+    fn unpack(bytes: &mut &'buf [u8]) -> Result<Self, UnpackErr>
+    where
+        Self: Sized,
+    {
+        Self::unpack_with_ctx(bytes, &mut UnpackCtx::new())
+    }
+
+    fn unpack_with_ctx(bytes: &mut &'buf [u8], ctx: &mut UnpackCtx) -> Result<Self, UnpackErr>
+    where
+        Self: Sized,
+    {
+        let [start, end] = <[T; 2]>::unpack_with_ctx(bytes, ctx)?;
+        Ok(start..=end)
+    }
+}
+
+/// Ranges without an end are packed as an array of `[start]`, like in rmp_serde.
+impl<T: MsgPack> MsgPack for RangeFrom<T> {
+    fn pack(&self) -> impl Iterator<Item = Piece<'_>> {
+        pack_array_header(1).chain(self.start.pack())
+    }
+
+    fn pack_with_writer(&self, w: &mut dyn Write) -> Result<usize, PackErr> {
+        Ok(write_pieces(pack_array_header(1), w)? + self.start.pack_with_writer(w)?)
+    }
+}
+
+impl<'buf, T: MsgUnpack<'buf>> MsgUnpack<'buf> for RangeFrom<T> {
+    fn unpack(bytes: &mut &'buf [u8]) -> Result<Self, UnpackErr>
+    where
+        Self: Sized,
+    {
+        Self::unpack_with_ctx(bytes, &mut UnpackCtx::new())
+    }
+
+    fn unpack_with_ctx(bytes: &mut &'buf [u8], ctx: &mut UnpackCtx) -> Result<Self, UnpackErr>
+    where
+        Self: Sized,
+    {
+        let [start] = <[T; 1]>::unpack_with_ctx(bytes, ctx)?;
+        Ok(start..)
+    }
+}
+
+/// Ranges without a start are packed as an array of `[end]`, like in rmp_serde.
+impl<T: MsgPack> MsgPack for RangeTo<T> {
+    fn pack(&self) -> impl Iterator<Item = Piece<'_>> {
+        pack_array_header(1).chain(self.end.pack())
+    }
+
+    fn pack_with_writer(&self, w: &mut dyn Write) -> Result<usize, PackErr> {
+        Ok(write_pieces(pack_array_header(1), w)? + self.end.pack_with_writer(w)?)
+    }
+}
+
+impl<'buf, T: MsgUnpack<'buf>> MsgUnpack<'buf> for RangeTo<T> {
+    fn unpack(bytes: &mut &'buf [u8]) -> Result<Self, UnpackErr>
+    where
+        Self: Sized,
+    {
+        Self::unpack_with_ctx(bytes, &mut UnpackCtx::new())
+    }
+
+    fn unpack_with_ctx(bytes: &mut &'buf [u8], ctx: &mut UnpackCtx) -> Result<Self, UnpackErr>
+    where
+        Self: Sized,
+    {
+        let [end] = <[T; 1]>::unpack_with_ctx(bytes, ctx)?;
+        Ok(..end)
+    }
+}
+
+/// PhantomData is packed as an empty array, which is how rmp_serde packs unit structs.
+impl<T: ?Sized> MsgPack for PhantomData<T> {
+    fn pack(&self) -> impl Iterator<Item = Piece<'_>> {
+        pack_array_header(0)
+    }
+}
+
+impl<'buf, T: ?Sized> MsgUnpack<'buf> for PhantomData<T> {
+    fn unpack(bytes: &mut &'buf [u8]) -> Result<Self, UnpackErr>
+    where
+        Self: Sized,
+    {
+        Self::unpack_with_ctx(bytes, &mut UnpackCtx::new())
+    }
+
+    fn unpack_with_ctx(bytes: &mut &'buf [u8], ctx: &mut UnpackCtx) -> Result<Self, UnpackErr>
+    where
+        Self: Sized,
+    {
+        check_len(unpack_array_header_with_ctx(bytes, ctx)?, 0)?;
+        Ok(PhantomData)
+    }
+}
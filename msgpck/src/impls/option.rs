@@ -2,7 +2,7 @@ use core::iter;
 
 use crate::{
     util::{slice_take, Either},
-    Marker, MsgPack, MsgUnpack, Piece, UnpackErr,
+    ExpectedKind, Marker, MsgPack, MsgUnpack, Piece, UnpackCtx, UnpackErr,
 };
 
 impl<T: MsgPack> MsgPack for Option<T> {
@@ -24,6 +24,16 @@ impl<'buf, T: MsgUnpack<'buf>> MsgUnpack<'buf> for Option<T> {
     /// Note that `Option<Option<T>>` will never unpack into `Some(None)` because of how Optionals
     /// are represented in msgpack.
     fn unpack(bytes: &mut &'buf [u8]) -> Result<Self, crate::UnpackErr>
+    where
+        Self: Sized,
+    {
+        Self::unpack_with_ctx(bytes, &mut UnpackCtx::new())
+    }
+
+    fn unpack_with_ctx(
+        bytes: &mut &'buf [u8],
+        ctx: &mut UnpackCtx,
+    ) -> Result<Self, crate::UnpackErr>
     where
         Self: Sized,
     {
@@ -36,7 +46,13 @@ impl<'buf, T: MsgUnpack<'buf>> MsgUnpack<'buf> for Option<T> {
             return Ok(None);
         }
 
-        let t = T::unpack(bytes)?;
+        let t = T::unpack_with_ctx(bytes, ctx).map_err(|e| match e {
+            // nil would also have been accepted
+            UnpackErr::WrongMarker { found, expected } => {
+                UnpackErr::wrong_marker(found, expected | ExpectedKind::NIL)
+            }
+            e => e,
+        })?;
         Ok(Some(t))
     }
 }
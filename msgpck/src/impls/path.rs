@@ -0,0 +1,105 @@
+use std::ffi::OsStr;
+use std::path::{Path, PathBuf};
+
+use super::uints::add_bin_kind;
+use crate::{
+    marker::{ExpectedKind, Marker},
+    util::Either,
+    MsgPack, MsgUnpack, PackErr, Piece, UnpackCtx, UnpackErr, Write,
+};
+
+/// Paths are packed as strings, like in rmp_serde.
+///
+/// rmp_serde can't represent paths that aren't valid UTF-8, so those are packed as a bin of their
+/// bytes instead. On Unix, either representation may be unpacked. On other platforms, only a bin
+/// that is valid UTF-8 may be unpacked, since the bytes of their paths aren't portable.
+impl MsgPack for Path {
+    fn pack(&self) -> impl Iterator<Item = Piece<'_>> {
+        match self.to_str() {
+            Some(s) => Either::A(s.pack()),
+            None => Either::B(os_str_bytes(self.as_os_str()).pack()),
+        }
+    }
+}
+
+impl MsgPack for &Path {
+    fn pack(&self) -> impl Iterator<Item = Piece<'_>> {
+        Path::pack(self)
+    }
+
+    fn pack_with_writer(&self, w: &mut dyn Write) -> Result<usize, PackErr> {
+        Path::pack_with_writer(self, w)
+    }
+}
+
+impl<'buf> MsgUnpack<'buf> for &'buf Path {
+    fn unpack(bytes: &mut &'buf [u8]) -> Result<Self, UnpackErr>
+    where
+        Self: Sized,
+    {
+        Self::unpack_with_ctx(bytes, &mut UnpackCtx::new())
+    }
+
+    fn unpack_with_ctx(bytes: &mut &'buf [u8], ctx: &mut UnpackCtx) -> Result<Self, UnpackErr>
+    where
+        Self: Sized,
+    {
+        let &b = bytes.first().ok_or(UnpackErr::UnexpectedEof)?;
+
+        match ExpectedKind::of(Marker::from_u8(b)) {
+            ExpectedKind::BIN => {
+                let bin: &[u8] = MsgUnpack::unpack_with_ctx(bytes, ctx)?;
+                os_str_from_bytes(bin).map(Path::new)
+            }
+            _ => <&str>::unpack_with_ctx(bytes, ctx)
+                .map(Path::new)
+                .map_err(add_bin_kind),
+        }
+    }
+}
+
+impl MsgPack for PathBuf {
+    fn pack(&self) -> impl Iterator<Item = Piece<'_>> {
+        self.as_path().pack()
+    }
+
+    fn pack_with_writer(&self, w: &mut dyn Write) -> Result<usize, PackErr> {
+        self.as_path().pack_with_writer(w)
+    }
+}
+
+impl<'buf> MsgUnpack<'buf> for PathBuf {
+    fn unpack(bytes: &mut &'buf [u8]) -> Result<Self, UnpackErr>
+    where
+        Self: Sized,
+    {
+        Self::unpack_with_ctx(bytes, &mut UnpackCtx::new())
+    }
+
+    fn unpack_with_ctx(bytes: &mut &'buf [u8], ctx: &mut UnpackCtx) -> Result<Self, UnpackErr>
+    where
+        Self: Sized,
+    {
+        <&Path>::unpack_with_ctx(bytes, ctx).map(PathBuf::from)
+    }
+}
+
+#[cfg(unix)]
+fn os_str_bytes(s: &OsStr) -> &[u8] {
+    std::os::unix::ffi::OsStrExt::as_bytes(s)
+}
+
+#[cfg(not(unix))]
+fn os_str_bytes(s: &OsStr) -> &[u8] {
+    s.as_encoded_bytes()
+}
+
+#[cfg(unix)]
+fn os_str_from_bytes(bytes: &[u8]) -> Result<&OsStr, UnpackErr> {
+    Ok(std::os::unix::ffi::OsStrExt::from_bytes(bytes))
+}
+
+#[cfg(not(unix))]
+fn os_str_from_bytes(bytes: &[u8]) -> Result<&OsStr, UnpackErr> {
+    Ok(OsStr::new(core::str::from_utf8(bytes)?))
+}
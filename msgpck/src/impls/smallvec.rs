@@ -0,0 +1,38 @@
+use smallvec::{Array, SmallVec};
+
+use crate::{
+    util::{pack_array, unpack_array},
+    MsgPack, MsgUnpack, Piece, UnpackCtx, UnpackErr,
+};
+
+impl<A> MsgPack for SmallVec<A>
+where
+    A: Array,
+    A::Item: MsgPack,
+{
+    fn pack(&self) -> impl Iterator<Item = Piece<'_>> {
+        pack_array(self.len(), self.iter())
+    }
+}
+
+/// Small vectors spill onto the heap instead of overflowing, so they can be unpacked from arrays
+/// of any length.
+impl<'buf, A> MsgUnpack<'buf> for SmallVec<A>
+where
+    A: Array,
+    A::Item: MsgUnpack<'buf>,
+{
+    fn unpack(bytes: &mut &'buf [u8]) -> Result<Self, UnpackErr>
+    where
+        Self: Sized,
+    {
+        Self::unpack_with_ctx(bytes, &mut UnpackCtx::new())
+    }
+
+    fn unpack_with_ctx(bytes: &mut &'buf [u8], ctx: &mut UnpackCtx) -> Result<Self, UnpackErr>
+    where
+        Self: Sized,
+    {
+        unpack_array(bytes, ctx)
+    }
+}
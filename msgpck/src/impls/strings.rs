@@ -1,26 +1,31 @@
-use crate::{marker::Marker, util::slice_take, MsgPack, MsgUnpack, Piece, UnpackErr};
-use core::str::from_utf8;
+use crate::{
+    marker::{ExpectedKind, Marker},
+    util::slice_take,
+    MsgPack, MsgUnpack, Piece, UnpackCtx, UnpackErr,
+};
+use core::{iter, str::from_utf8};
 
 impl MsgPack for str {
     fn pack(&self) -> impl Iterator<Item = Piece<'_>> {
         let marker_piece;
         let len_piece;
 
-        match self.len() {
-            ..=0x1f => {
-                marker_piece = Marker::FixStr(self.len() as u8).into();
+        let marker = str_marker(self.len());
+        match marker {
+            Marker::FixStr(_) => {
+                marker_piece = marker.into();
                 len_piece = None;
             }
-            ..=0xff => {
-                marker_piece = Marker::Str8.into();
+            Marker::Str8 => {
+                marker_piece = marker.into();
                 len_piece = Some((self.len() as u8).into());
             }
-            ..=0xffff => {
-                marker_piece = Marker::Str16.into();
+            Marker::Str16 => {
+                marker_piece = marker.into();
                 len_piece = Some((self.len() as u16).into());
             }
             _ => {
-                marker_piece = Marker::Str32.into();
+                marker_piece = marker.into();
                 len_piece = Some((self.len() as u32).into());
             }
         }
@@ -43,18 +48,28 @@ impl MsgPack for &str {
 
 impl<'buf> MsgUnpack<'buf> for &'buf str {
     fn unpack(bytes: &mut &'buf [u8]) -> Result<Self, UnpackErr>
+    where
+        Self: Sized,
+    {
+        Self::unpack_with_ctx(bytes, &mut UnpackCtx::new())
+    }
+
+    fn unpack_with_ctx(bytes: &mut &'buf [u8], ctx: &mut UnpackCtx) -> Result<Self, UnpackErr>
     where
         Self: Sized,
     {
         let &[b] = slice_take(bytes)?;
-        let len: usize = match Marker::from_u8(b) {
+        let marker = Marker::from_u8(b);
+        let len: usize = match marker {
             Marker::FixStr(len) => len.into(),
             Marker::Str8 => slice_take::<_, 1>(bytes)?[0].into(),
             Marker::Str16 => u16::from_be_bytes(*slice_take(bytes)?).into(),
             Marker::Str32 => u32::from_be_bytes(*slice_take(bytes)?).try_into()?,
-            m => return Err(UnpackErr::WrongMarker(m)),
+            m => return Err(UnpackErr::wrong_marker(m, ExpectedKind::STR)),
         };
 
+        crate::util::check_canonical(ctx, marker, str_marker(len))?;
+
         if len > bytes.len() {
             return Err(UnpackErr::UnexpectedEof);
         }
@@ -66,3 +81,46 @@ impl<'buf> MsgUnpack<'buf> for &'buf str {
         Ok(from_utf8(str_bytes)?)
     }
 }
+
+/// Chars are packed as a string containing a single character, like in rmp_serde.
+impl MsgPack for char {
+    fn pack(&self) -> impl Iterator<Item = Piece<'_>> {
+        let mut utf8 = [0u8; 4];
+        let len = self.encode_utf8(&mut utf8).len();
+
+        iter::once(Marker::FixStr(len as u8).into())
+            .chain(utf8.into_iter().take(len).map(Piece::Byte))
+    }
+}
+
+impl<'buf> MsgUnpack<'buf> for char {
+    fn unpack(bytes: &mut &'buf [u8]) -> Result<Self, UnpackErr>
+    where
+        Self: Sized,
+    {
+        Self::unpack_with_ctx(bytes, &mut UnpackCtx::new())
+    }
+
+    fn unpack_with_ctx(bytes: &mut &'buf [u8], ctx: &mut UnpackCtx) -> Result<Self, UnpackErr>
+    where
+        Self: Sized,
+    {
+        let s: &str = MsgUnpack::unpack_with_ctx(bytes, ctx)?;
+        let mut chars = s.chars();
+
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => Ok(c),
+            _ => Err(UnpackErr::InvalidChar),
+        }
+    }
+}
+
+/// The marker of a string with the given length.
+const fn str_marker(len: usize) -> Marker {
+    match len {
+        ..=0x1f => Marker::FixStr(len as u8),
+        ..=0xff => Marker::Str8,
+        ..=0xffff => Marker::Str16,
+        _ => Marker::Str32,
+    }
+}
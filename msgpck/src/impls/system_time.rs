@@ -0,0 +1,75 @@
+use core::time::Duration;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use super::duration::pack_duration;
+use crate::{
+    marker::{ExpectedKind, Marker},
+    timestamp::pack_timestamp,
+    util::Either,
+    MsgPack, MsgUnpack, Piece, Timestamp, UnpackCtx, UnpackErr,
+};
+
+/// System times are packed as the [Duration] since [UNIX_EPOCH], like in rmp_serde.
+///
+/// rmp_serde can't represent times earlier than [UNIX_EPOCH], so those are packed as a
+/// [Timestamp] instead. Either representation may be unpacked.
+impl MsgPack for SystemTime {
+    fn pack(&self) -> impl Iterator<Item = Piece<'_>> {
+        match self.duration_since(UNIX_EPOCH) {
+            Ok(since_epoch) => Either::A(pack_duration(since_epoch)),
+            Err(e) => Either::B(pack_timestamp(timestamp_before_epoch(e.duration()))),
+        }
+    }
+}
+
+impl<'buf> MsgUnpack<'buf> for SystemTime {
+    fn unpack(bytes: &mut &'buf [u8]) -> Result<Self, UnpackErr>
+    where
+        Self: Sized,
+    {
+        Self::unpack_with_ctx(bytes, &mut UnpackCtx::new())
+    }
+
+    fn unpack_with_ctx(bytes: &mut &'buf [u8], ctx: &mut UnpackCtx) -> Result<Self, UnpackErr>
+    where
+        Self: Sized,
+    {
+        let &b = bytes.first().ok_or(UnpackErr::UnexpectedEof)?;
+
+        if ExpectedKind::of(Marker::from_u8(b)) == ExpectedKind::ARRAY {
+            return UNIX_EPOCH
+                .checked_add(Duration::unpack_with_ctx(bytes, ctx)?)
+                .ok_or(UnpackErr::Other("overflow unpacking SystemTime"));
+        }
+
+        let ts = Timestamp::unpack_with_ctx(bytes, ctx).map_err(|e| match e {
+            UnpackErr::WrongMarker { found, expected } => {
+                UnpackErr::wrong_marker(found, expected | ExpectedKind::ARRAY)
+            }
+            e => e,
+        })?;
+
+        let seconds = Duration::from_secs(ts.seconds().unsigned_abs());
+        let time = if ts.seconds() < 0 {
+            UNIX_EPOCH.checked_sub(seconds)
+        } else {
+            UNIX_EPOCH.checked_add(seconds)
+        };
+
+        time.and_then(|t| t.checked_add(Duration::from_nanos(ts.nanoseconds().into())))
+            .ok_or(UnpackErr::Other("overflow unpacking SystemTime"))
+    }
+}
+
+/// The timestamp of a time which is `before` the Unix epoch.
+fn timestamp_before_epoch(before: Duration) -> Timestamp {
+    let seconds = i64::try_from(before.as_secs()).map_or(i64::MIN, |s| -s);
+
+    // the nanoseconds of a timestamp count forward from its seconds
+    let (seconds, nanoseconds) = match before.subsec_nanos() {
+        0 => (seconds, 0),
+        nanos => (seconds.saturating_sub(1), 1_000_000_000 - nanos),
+    };
+
+    Timestamp::new(seconds, nanoseconds).expect("nanoseconds are less than a second")
+}
@@ -0,0 +1,74 @@
+//! Implementations for the `time` crate, enabled by the `time` feature.
+
+use time::{format_description::well_known::Rfc3339, OffsetDateTime, PrimitiveDateTime, UtcOffset};
+
+use crate::{
+    timestamp::{pack_timestamp, unpack_timestamp_or_str, TimestampOrStr},
+    ConversionErr, MsgPack, MsgUnpack, Piece, Timestamp, UnpackCtx, UnpackErr,
+};
+
+/// Date-times are packed using the msgpack timestamp extension type, which doesn't include the
+/// offset. They are unpacked in UTC.
+///
+/// They may also be unpacked from an integer number of seconds since the Unix epoch, or from an
+/// RFC 3339 string like `2023-11-14T22:13:20Z`, except in
+/// [UnpackMode::Canonical](crate::UnpackMode::Canonical) mode.
+/// Date-times unpacked from a string keep its offset.
+impl MsgPack for OffsetDateTime {
+    fn pack(&self) -> impl Iterator<Item = Piece<'_>> {
+        let ts = Timestamp::new(self.unix_timestamp(), self.nanosecond())
+            .expect("nanoseconds are less than a second");
+        pack_timestamp(ts)
+    }
+}
+
+impl<'buf> MsgUnpack<'buf> for OffsetDateTime {
+    fn unpack(bytes: &mut &'buf [u8]) -> Result<Self, UnpackErr>
+    where
+        Self: Sized,
+    {
+        Self::unpack_with_ctx(bytes, &mut UnpackCtx::new())
+    }
+
+    fn unpack_with_ctx(bytes: &mut &'buf [u8], ctx: &mut UnpackCtx) -> Result<Self, UnpackErr>
+    where
+        Self: Sized,
+    {
+        match unpack_timestamp_or_str(bytes, ctx)? {
+            TimestampOrStr::Timestamp(ts) => {
+                let nanos = i128::from(ts.seconds()) * 1_000_000_000 + i128::from(ts.nanoseconds());
+                OffsetDateTime::from_unix_timestamp_nanos(nanos)
+                    .map_err(|_| UnpackErr::Other("timestamp out of range for OffsetDateTime"))
+            }
+            TimestampOrStr::Str(s) => OffsetDateTime::parse(s, &Rfc3339)
+                .map_err(|e| UnpackErr::Conversion(ConversionErr::new(e))),
+        }
+    }
+}
+
+/// Primitive date-times are assumed to be in UTC, and are packed like an [OffsetDateTime].
+impl MsgPack for PrimitiveDateTime {
+    fn pack(&self) -> impl Iterator<Item = Piece<'_>> {
+        let dt = self.assume_utc();
+        let ts = Timestamp::new(dt.unix_timestamp(), dt.nanosecond())
+            .expect("nanoseconds are less than a second");
+        pack_timestamp(ts)
+    }
+}
+
+impl<'buf> MsgUnpack<'buf> for PrimitiveDateTime {
+    fn unpack(bytes: &mut &'buf [u8]) -> Result<Self, UnpackErr>
+    where
+        Self: Sized,
+    {
+        Self::unpack_with_ctx(bytes, &mut UnpackCtx::new())
+    }
+
+    fn unpack_with_ctx(bytes: &mut &'buf [u8], ctx: &mut UnpackCtx) -> Result<Self, UnpackErr>
+    where
+        Self: Sized,
+    {
+        let dt = OffsetDateTime::unpack_with_ctx(bytes, ctx)?.to_offset(UtcOffset::UTC);
+        Ok(PrimitiveDateTime::new(dt.date(), dt.time()))
+    }
+}
@@ -0,0 +1,73 @@
+use tinyvec::{Array, ArrayVec, TinyVec};
+
+use crate::{
+    util::{pack_array, unpack_array, unpack_array_with},
+    MsgPack, MsgUnpack, Piece, UnpackCtx, UnpackErr,
+};
+
+impl<A> MsgPack for ArrayVec<A>
+where
+    A: Array,
+    A::Item: MsgPack,
+{
+    fn pack(&self) -> impl Iterator<Item = Piece<'_>> {
+        pack_array(self.len(), self.iter())
+    }
+}
+
+impl<'buf, A> MsgUnpack<'buf> for ArrayVec<A>
+where
+    A: Array,
+    A::Item: MsgUnpack<'buf>,
+{
+    fn unpack(bytes: &mut &'buf [u8]) -> Result<Self, UnpackErr>
+    where
+        Self: Sized,
+    {
+        Self::unpack_with_ctx(bytes, &mut UnpackCtx::new())
+    }
+
+    fn unpack_with_ctx(bytes: &mut &'buf [u8], ctx: &mut UnpackCtx) -> Result<Self, UnpackErr>
+    where
+        Self: Sized,
+    {
+        unpack_array_with(bytes, ctx, ArrayVec::new(), |vec, elem| {
+            match vec.try_push(elem) {
+                Some(elem) => Err(elem),
+                None => Ok(()),
+            }
+        })
+    }
+}
+
+impl<A> MsgPack for TinyVec<A>
+where
+    A: Array,
+    A::Item: MsgPack,
+{
+    fn pack(&self) -> impl Iterator<Item = Piece<'_>> {
+        pack_array(self.len(), self.iter())
+    }
+}
+
+/// Tiny vectors move onto the heap instead of overflowing, so they can be unpacked from arrays of
+/// any length.
+impl<'buf, A> MsgUnpack<'buf> for TinyVec<A>
+where
+    A: Array,
+    A::Item: MsgUnpack<'buf>,
+{
+    fn unpack(bytes: &mut &'buf [u8]) -> Result<Self, UnpackErr>
+    where
+        Self: Sized,
+    {
+        Self::unpack_with_ctx(bytes, &mut UnpackCtx::new())
+    }
+
+    fn unpack_with_ctx(bytes: &mut &'buf [u8], ctx: &mut UnpackCtx) -> Result<Self, UnpackErr>
+    where
+        Self: Sized,
+    {
+        unpack_array(bytes, ctx)
+    }
+}
@@ -0,0 +1,85 @@
+use core::iter;
+
+use super::arrays::check_len;
+use crate::{
+    util::{pack_array_header, slice_take, unpack_array_header_with_ctx, write_pieces},
+    ExpectedKind, Marker, MsgPack, MsgUnpack, PackErr, PathSegment, Piece, UnpackCtx, UnpackErr,
+    Write,
+};
+
+/// The unit type is packed as nil, like in rmp_serde.
+impl MsgPack for () {
+    fn pack(&self) -> impl Iterator<Item = Piece<'_>> {
+        iter::once(Marker::Null.into())
+    }
+}
+
+impl<'buf> MsgUnpack<'buf> for () {
+    fn unpack(bytes: &mut &'buf [u8]) -> Result<Self, UnpackErr>
+    where
+        Self: Sized,
+    {
+        let &[b] = slice_take(bytes)?;
+        match Marker::from_u8(b) {
+            Marker::Null => Ok(()),
+            m => Err(UnpackErr::wrong_marker(m, ExpectedKind::NIL)),
+        }
+    }
+}
+
+/// Implement [MsgPack] and [MsgUnpack] for a tuple, which is packed as an array of its elements.
+macro_rules! impl_tuple {
+    ($len:literal => $($T:ident $i:tt),+) => {
+        impl<$($T: MsgPack),+> MsgPack for ($($T,)+) {
+            fn pack(&self) -> impl Iterator<Item = Piece<'_>> {
+                pack_array_header($len)$(.chain(self.$i.pack()))+
+            }
+
+            fn pack_with_writer(&self, w: &mut dyn Write) -> Result<usize, PackErr> {
+                let mut n = write_pieces(pack_array_header($len), w)?;
+                $(n += self.$i.pack_with_writer(w)?;)+
+                Ok(n)
+            }
+        }
+
+        impl<'buf, $($T: MsgUnpack<'buf>),+> MsgUnpack<'buf> for ($($T,)+) {
+            fn unpack(bytes: &mut &'buf [u8]) -> Result<Self, UnpackErr>
+            where
+                Self: Sized,
+            {
+                Self::unpack_with_ctx(bytes, &mut UnpackCtx::new())
+            }
+
+            fn unpack_with_ctx(bytes: &mut &'buf [u8], ctx: &mut UnpackCtx) -> Result<Self, UnpackErr>
+            where
+                Self: Sized,
+            {
+                let n = unpack_array_header_with_ctx(bytes, ctx)?;
+                check_len(n, $len)?;
+
+                Ok(($({
+                    let start = *bytes;
+                    $T::unpack_with_ctx(bytes, ctx)
+                        .map_err(|e| ctx.error_at(PathSegment::Index($i), start, e))?
+                },)+))
+            }
+        }
+    };
+}
+
+impl_tuple!(1 => A 0);
+impl_tuple!(2 => A 0, B 1);
+impl_tuple!(3 => A 0, B 1, C 2);
+impl_tuple!(4 => A 0, B 1, C 2, D 3);
+impl_tuple!(5 => A 0, B 1, C 2, D 3, E 4);
+impl_tuple!(6 => A 0, B 1, C 2, D 3, E 4, F 5);
+impl_tuple!(7 => A 0, B 1, C 2, D 3, E 4, F 5, G 6);
+impl_tuple!(8 => A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7);
+impl_tuple!(9 => A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8);
+impl_tuple!(10 => A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8, J 9);
+impl_tuple!(11 => A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8, J 9, K 10);
+impl_tuple!(12 => A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8, J 9, K 10, L 11);
+impl_tuple!(13 => A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8, J 9, K 10, L 11, M 12);
+impl_tuple!(14 => A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8, J 9, K 10, L 11, M 12, N 13);
+impl_tuple!(15 => A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8, J 9, K 10, L 11, M 12, N 13, O 14);
+impl_tuple!(16 => A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8, J 9, K 10, L 11, M 12, N 13, O 14, P 15);
@@ -1,4 +1,9 @@
-use crate::{marker::Marker, piece::Pair, util::slice_take, MsgPack, MsgUnpack, Piece, UnpackErr};
+use crate::{
+    marker::{ExpectedKind, Marker},
+    piece::Pair,
+    util::slice_take,
+    MsgPack, MsgUnpack, Piece, UnpackCtx, UnpackErr,
+};
 
 impl MsgPack for u8 {
     fn pack(&self) -> impl Iterator<Item = Piece<'_>> {
@@ -24,12 +29,26 @@ impl MsgPack for u64 {
     }
 }
 
+/// 128-bit integers are packed as a bin of 16 big-endian bytes, like in rmp_serde.
+impl MsgPack for u128 {
+    fn pack(&self) -> impl Iterator<Item = Piece<'_>> {
+        pack_int128(self.to_be_bytes())
+    }
+}
+
 impl<'buf> MsgUnpack<'buf> for u8 {
     fn unpack(bytes: &mut &'buf [u8]) -> Result<Self, UnpackErr>
     where
         Self: Sized,
     {
-        let n = unpack_u64(bytes)?;
+        Self::unpack_with_ctx(bytes, &mut UnpackCtx::new())
+    }
+
+    fn unpack_with_ctx(bytes: &mut &'buf [u8], ctx: &mut UnpackCtx) -> Result<Self, UnpackErr>
+    where
+        Self: Sized,
+    {
+        let n = unpack_u64(bytes, ctx)?;
         Ok(n.try_into()?)
     }
 }
@@ -39,7 +58,14 @@ impl<'buf> MsgUnpack<'buf> for u16 {
     where
         Self: Sized,
     {
-        let n = unpack_u64(bytes)?;
+        Self::unpack_with_ctx(bytes, &mut UnpackCtx::new())
+    }
+
+    fn unpack_with_ctx(bytes: &mut &'buf [u8], ctx: &mut UnpackCtx) -> Result<Self, UnpackErr>
+    where
+        Self: Sized,
+    {
+        let n = unpack_u64(bytes, ctx)?;
         Ok(n.try_into()?)
     }
 }
@@ -49,7 +75,14 @@ impl<'buf> MsgUnpack<'buf> for u32 {
     where
         Self: Sized,
     {
-        let n = unpack_u64(bytes)?;
+        Self::unpack_with_ctx(bytes, &mut UnpackCtx::new())
+    }
+
+    fn unpack_with_ctx(bytes: &mut &'buf [u8], ctx: &mut UnpackCtx) -> Result<Self, UnpackErr>
+    where
+        Self: Sized,
+    {
+        let n = unpack_u64(bytes, ctx)?;
         Ok(n.try_into()?)
     }
 }
@@ -59,21 +92,118 @@ impl<'buf> MsgUnpack<'buf> for u64 {
     where
         Self: Sized,
     {
-        unpack_u64(bytes)
+        Self::unpack_with_ctx(bytes, &mut UnpackCtx::new())
+    }
+
+    fn unpack_with_ctx(bytes: &mut &'buf [u8], ctx: &mut UnpackCtx) -> Result<Self, UnpackErr>
+    where
+        Self: Sized,
+    {
+        unpack_u64(bytes, ctx)
     }
 }
 
-pub fn unpack_u64(bytes: &mut &[u8]) -> Result<u64, UnpackErr> {
+/// A 128-bit integer may also be unpacked from any integer that fits, except in
+/// [UnpackMode::Canonical](crate::UnpackMode::Canonical) mode.
+impl<'buf> MsgUnpack<'buf> for u128 {
+    fn unpack(bytes: &mut &'buf [u8]) -> Result<Self, UnpackErr>
+    where
+        Self: Sized,
+    {
+        Self::unpack_with_ctx(bytes, &mut UnpackCtx::new())
+    }
+
+    fn unpack_with_ctx(bytes: &mut &'buf [u8], ctx: &mut UnpackCtx) -> Result<Self, UnpackErr>
+    where
+        Self: Sized,
+    {
+        match unpack_int128_bin(bytes, ctx)? {
+            Some(be_bytes) => Ok(u128::from_be_bytes(be_bytes)),
+            None => Ok(unpack_u64(bytes, ctx).map_err(add_bin_kind)?.into()),
+        }
+    }
+}
+
+/// Pack the big-endian bytes of a 128-bit integer as a bin.
+pub(crate) fn pack_int128<'a>(be_bytes: [u8; 16]) -> impl Iterator<Item = Piece<'a>> {
+    let (high, low) = be_bytes.split_at(8);
+    [
+        Marker::Bin8.into(),
+        Piece::Byte(16),
+        Piece::Bytes8(high.try_into().expect("8 bytes")),
+        Piece::Bytes8(low.try_into().expect("8 bytes")),
+    ]
+    .into_iter()
+}
+
+/// Unpack the big-endian bytes of a 128-bit integer that was packed as a bin.
+///
+/// Returns `None` without consuming anything if the next value isn't a bin, in which case it
+/// should be unpacked as a regular integer.
+pub(crate) fn unpack_int128_bin(
+    bytes: &mut &[u8],
+    ctx: &mut UnpackCtx,
+) -> Result<Option<[u8; 16]>, UnpackErr> {
+    let &b = bytes.first().ok_or(UnpackErr::UnexpectedEof)?;
+
+    match ExpectedKind::of(Marker::from_u8(b)) {
+        ExpectedKind::BIN => {
+            let bin: &[u8] = MsgUnpack::unpack_with_ctx(bytes, ctx)?;
+            let be_bytes = bin.try_into().map_err(|_| UnpackErr::InvalidInt128)?;
+            Ok(Some(be_bytes))
+        }
+
+        // 128-bit integers are always packed as bin
+        ExpectedKind::UINT | ExpectedKind::INT if ctx.is_canonical() => {
+            Err(UnpackErr::NonCanonical)
+        }
+
+        _ => Ok(None),
+    }
+}
+
+/// Add bin to the expected kinds of a [UnpackErr::WrongMarker], for values that may also be
+/// unpacked from a bin, like 128-bit integers.
+pub(crate) fn add_bin_kind(e: UnpackErr) -> UnpackErr {
+    match e {
+        UnpackErr::WrongMarker { found, expected } => {
+            UnpackErr::wrong_marker(found, expected | ExpectedKind::BIN)
+        }
+        e => e,
+    }
+}
+
+/// Unpack an unsigned integer.
+///
+/// Signed integer markers are also accepted, as long as the value isn't negative, unless `ctx` is
+/// [strict](UnpackCtx::is_strict).
+pub fn unpack_u64(bytes: &mut &[u8], ctx: &UnpackCtx) -> Result<u64, UnpackErr> {
     let &[b] = slice_take(bytes)?;
+    let marker = Marker::from_u8(b);
 
-    Ok(match Marker::from_u8(b) {
+    let n = match marker {
         Marker::FixPos(n) => n.into(),
         Marker::U8 => slice_take::<u8, 1>(bytes)?[0].into(),
         Marker::U16 => u16::from_be_bytes(*slice_take(bytes)?).into(),
         Marker::U32 => u32::from_be_bytes(*slice_take(bytes)?).into(),
         Marker::U64 => u64::from_be_bytes(*slice_take(bytes)?),
-        m => return Err(UnpackErr::WrongMarker(m)),
-    })
+        m if ctx.is_strict() => return Err(UnpackErr::wrong_marker(m, ExpectedKind::UINT)),
+        Marker::FixNeg(i) => i.try_into()?,
+        Marker::I8 => i8::from_be_bytes(*slice_take(bytes)?).try_into()?,
+        Marker::I16 => i16::from_be_bytes(*slice_take(bytes)?).try_into()?,
+        Marker::I32 => i32::from_be_bytes(*slice_take(bytes)?).try_into()?,
+        Marker::I64 => i64::from_be_bytes(*slice_take(bytes)?).try_into()?,
+        m => {
+            return Err(UnpackErr::wrong_marker(
+                m,
+                ExpectedKind::UINT | ExpectedKind::INT,
+            ))
+        }
+    };
+
+    crate::util::check_canonical(ctx, marker, pack_u64(n).marker())?;
+
+    Ok(n)
 }
 
 pub const fn pack_u64<'a>(n: u64) -> Pair<'a> {
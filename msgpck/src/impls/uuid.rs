@@ -0,0 +1,47 @@
+use uuid::Uuid;
+
+use crate::{
+    bytes::unpack_bytes, ConversionErr, ExpectedKind, Marker, MsgPack, MsgUnpack, Piece, UnpackCtx,
+    UnpackErr,
+};
+
+/// UUIDs are packed as a bin of their 16 bytes, like in rmp_serde.
+impl MsgPack for Uuid {
+    fn pack(&self) -> impl Iterator<Item = Piece<'_>> {
+        self.as_bytes().as_slice().pack()
+    }
+}
+
+/// UUIDs may be unpacked from a bin or an array of 16 bytes, or from a string like
+/// `67e55044-10b1-426f-9247-bb680e5fe0c8`, for compatibility with data that packed them as strings.
+impl<'buf> MsgUnpack<'buf> for Uuid {
+    fn unpack(bytes: &mut &'buf [u8]) -> Result<Self, UnpackErr>
+    where
+        Self: Sized,
+    {
+        Self::unpack_with_ctx(bytes, &mut UnpackCtx::new())
+    }
+
+    fn unpack_with_ctx(bytes: &mut &'buf [u8], ctx: &mut UnpackCtx) -> Result<Self, UnpackErr>
+    where
+        Self: Sized,
+    {
+        let &b = bytes.first().ok_or(UnpackErr::UnexpectedEof)?;
+
+        match Marker::from_u8(b) {
+            m if ExpectedKind::of(m) == ExpectedKind::STR => {
+                let s: &str = MsgUnpack::unpack_with_ctx(bytes, ctx)?;
+                Uuid::try_parse(s).map_err(|e| UnpackErr::Conversion(ConversionErr::new(e)))
+            }
+            m if ExpectedKind::of(m) == ExpectedKind::BIN
+                || ExpectedKind::of(m) == ExpectedKind::ARRAY =>
+            {
+                unpack_bytes(bytes, ctx).map(Uuid::from_bytes)
+            }
+            m => Err(UnpackErr::wrong_marker(
+                m,
+                ExpectedKind::BIN | ExpectedKind::ARRAY | ExpectedKind::STR,
+            )),
+        }
+    }
+}
@@ -34,21 +34,44 @@
 #[cfg(feature = "alloc")]
 extern crate alloc;
 
+mod bytes;
+#[cfg(feature = "alloc")]
+mod canonical_map;
+#[cfg(feature = "alloc")]
+mod canonical_set;
+mod context;
 mod enums;
 mod error;
+mod fields;
 mod impls;
+#[cfg(feature = "alloc")]
+mod map_vec;
 mod marker;
 mod packers;
 mod piece;
+mod seq;
+mod timestamp;
 mod util;
 mod write;
 
+#[cfg(feature = "alloc")]
+pub use bytes::ByteBuf;
+pub use bytes::Bytes;
+#[cfg(feature = "alloc")]
+pub use canonical_map::CanonicalMap;
+#[cfg(feature = "alloc")]
+pub use canonical_set::CanonicalSet;
+pub use context::{UnpackCtx, UnpackMode};
 pub use enums::{EnumHeader, Variant};
-pub use error::{PackErr, UnpackErr};
-pub use marker::Marker;
+pub use error::{ContextErr, ConversionErr, PackErr, PathSegment, UnpackErr};
+#[cfg(feature = "alloc")]
+pub use map_vec::MapVec;
+pub use marker::{ExpectedKind, Marker};
 pub use msgpck_derive::{MsgPack, MsgUnpack};
 pub use packers::*;
 pub use piece::Piece;
+pub use seq::Seq;
+pub use timestamp::Timestamp;
 pub use write::Write;
 
 /// Trait for serializing a type using msgpack.
@@ -56,6 +79,7 @@ pub trait MsgPack {
     /// Returns an iterator of msgpack [Piece]s. Collect them all to produce a valid msgpack value.
     ///
     /// ```
+    /// # #[cfg(feature = "alloc")] {
     /// use msgpck::MsgPack;
     /// let mut encoded = vec![];
     /// for m in vec![0xDDu8, 0xEE, 3].pack() {
@@ -63,6 +87,7 @@ pub trait MsgPack {
     /// }
     /// println!("{encoded:x?}");
     /// assert_eq!(encoded, [0x93, 0xcc, 0xdd, 0xcc, 0xee, 0x03]);
+    /// # }
     /// ```
     fn pack(&self) -> impl Iterator<Item = Piece<'_>>;
 
@@ -76,12 +101,7 @@ pub trait MsgPack {
     /// TODO: decide on whether to this takes a `impl Write` or a `dyn Write`, and in the latter
     /// case, how we should handle errors.
     fn pack_with_writer(&self, w: &mut dyn Write) -> Result<usize, PackErr> {
-        let mut n = 0;
-        for piece in self.pack() {
-            w.write_all(piece.as_bytes())?;
-            n += piece.as_bytes().len();
-        }
-        Ok(n)
+        util::write_pieces(self.pack(), w)
     }
 }
 
@@ -90,14 +110,30 @@ pub trait MsgUnpack<'buf> {
     /// Unpack a value from a msgpack bytes slice
     ///
     /// ```
+    /// # #[cfg(feature = "alloc")] {
     /// use msgpck::MsgUnpack;
     /// let encoded = [0x93, 0xCC, 0xDD, 0xCC, 0xEE, 3];
     /// let decoded: Vec<u8> = Vec::unpack(&mut &encoded[..]).unwrap();
     /// assert_eq!(decoded, &[0xDDu8, 0xEE, 3]);
+    /// # }
     /// ```
     fn unpack(bytes: &mut &'buf [u8]) -> Result<Self, UnpackErr>
     where
         Self: Sized;
+
+    /// Unpack a value from a msgpack bytes slice, using an [UnpackCtx].
+    ///
+    /// The default implementation just calls [MsgUnpack::unpack], but types that contain other
+    /// values should override it and pass `ctx` on when unpacking them, e.g. so that the path to a
+    /// value that failed to unpack is recorded. [MsgUnpack::unpack] should then be implemented by
+    /// calling this with [UnpackCtx::new].
+    fn unpack_with_ctx(bytes: &mut &'buf [u8], ctx: &mut UnpackCtx) -> Result<Self, UnpackErr>
+    where
+        Self: Sized,
+    {
+        let _ = ctx;
+        Self::unpack(bytes)
+    }
 }
 
 /// Helpers for packing/unpacking certain msgpack values.
@@ -105,10 +141,44 @@ pub trait MsgUnpack<'buf> {
 /// This module is used by the derive macros for [MsgPack] and [MsgUnpack].
 /// Unless you are implementing those traits by hand, you probably shouldn't be here.
 pub mod helpers {
-    pub use crate::enums::{pack_enum_header, pack_enum_header_to_writer, unpack_enum_header};
+    pub use crate::bytes::{unpack_bytes, UnpackBytes};
+    pub use crate::enums::{
+        pack_enum_header, pack_enum_header_to_writer, unpack_enum_header,
+        unpack_enum_header_with_ctx,
+    };
+    pub use crate::fields::{MapKeys, PackFields, PackMapFields, UnpackFields, UnpackMapFields};
+    pub use crate::impls::arrays::unpack_byte_array;
     pub use crate::impls::ints::{pack_i64, unpack_i64};
     pub use crate::impls::uints::{pack_u64, unpack_u64};
     pub use crate::util::{
-        pack_array_header, pack_map_header, unpack_array_header, unpack_map_header,
+        merge_map_entries, pack_array_header, pack_map_header, skip_value, unpack_array_header,
+        unpack_array_header_with_ctx, unpack_array_with, unpack_map_header,
+        unpack_map_header_with_ctx, unpack_map_key, unpack_map_with, unpack_with_type,
+        write_pieces,
     };
+
+    #[cfg(feature = "alloc")]
+    pub use crate::canonical_map::MapEntry;
+    #[cfg(feature = "alloc")]
+    pub use crate::canonical_set::SetElement;
+    #[cfg(feature = "alloc")]
+    pub use crate::util::{pack_array_sorted, pack_map_sorted, PackOwned};
+}
+
+/// Types for `heapless` 0.7, enabled by the `heapless07` feature.
+#[cfg(feature = "heapless07")]
+pub mod heapless07 {
+    pub use crate::impls::heapless07::ByteBuf;
+}
+
+/// Types for `heapless` 0.8, enabled by the `heapless08` feature.
+#[cfg(feature = "heapless08")]
+pub mod heapless08 {
+    pub use crate::impls::heapless08::ByteBuf;
+}
+
+/// Types for `heapless` 0.9, enabled by the `heapless09` feature.
+#[cfg(feature = "heapless09")]
+pub mod heapless09 {
+    pub use crate::impls::heapless09::ByteBuf;
 }
@@ -0,0 +1,94 @@
+use alloc::vec::Vec;
+use core::ops::{Deref, DerefMut};
+
+use crate::{
+    util::{pack_map, pack_map_header, unpack_map, write_pieces},
+    MsgPack, MsgUnpack, PackErr, Piece, UnpackCtx, UnpackErr, Write,
+};
+
+/// A list of key-value pairs which is packed as a msgpack map.
+///
+/// Unlike `HashMap` or `BTreeMap`, the entries are kept in the order that they were unpacked in,
+/// and no hashing or comparison of keys is done. This is useful for re-packing a map exactly like
+/// it was received.
+///
+/// Duplicate keys are kept as-is, except in [UnpackMode::Canonical](crate::UnpackMode::Canonical)
+/// mode, where unpacking a map with unsorted or duplicate keys fails.
+///
+/// ```
+/// use msgpck::{pack_vec, unpack_slice, MapVec};
+///
+/// let packed = [0x82, 0x02, 0xa1, b'b', 0x01, 0xa1, b'a'];
+/// let map: MapVec<u8, &str> = unpack_slice(&packed).unwrap();
+/// assert_eq!(map.0, [(2, "b"), (1, "a")]);
+/// assert_eq!(pack_vec(&map).unwrap(), packed);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct MapVec<K, V>(pub Vec<(K, V)>);
+
+impl<K: MsgPack, V: MsgPack> MsgPack for MapVec<K, V> {
+    fn pack(&self) -> impl Iterator<Item = Piece<'_>> {
+        pack_map(self.0.len(), self.0.iter().map(|(k, v)| (k, v)))
+    }
+
+    fn pack_with_writer(&self, w: &mut dyn Write) -> Result<usize, PackErr> {
+        let mut n = write_pieces(pack_map_header(self.0.len()), w)?;
+        for (k, v) in &self.0 {
+            n += k.pack_with_writer(w)?;
+            n += v.pack_with_writer(w)?;
+        }
+        Ok(n)
+    }
+}
+
+impl<'buf, K, V> MsgUnpack<'buf> for MapVec<K, V>
+where
+    K: MsgUnpack<'buf>,
+    V: MsgUnpack<'buf>,
+{
+    fn unpack(bytes: &mut &'buf [u8]) -> Result<Self, UnpackErr>
+    where
+        Self: Sized,
+    {
+        Self::unpack_with_ctx(bytes, &mut UnpackCtx::new())
+    }
+
+    fn unpack_with_ctx(bytes: &mut &'buf [u8], ctx: &mut UnpackCtx) -> Result<Self, UnpackErr>
+    where
+        Self: Sized,
+    {
+        unpack_map(bytes, ctx).map(MapVec)
+    }
+}
+
+impl<K, V> Default for MapVec<K, V> {
+    fn default() -> Self {
+        MapVec(Vec::new())
+    }
+}
+
+impl<K, V> From<Vec<(K, V)>> for MapVec<K, V> {
+    fn from(entries: Vec<(K, V)>) -> Self {
+        MapVec(entries)
+    }
+}
+
+impl<K, V> FromIterator<(K, V)> for MapVec<K, V> {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        MapVec(iter.into_iter().collect())
+    }
+}
+
+impl<K, V> Deref for MapVec<K, V> {
+    type Target = Vec<(K, V)>;
+
+    fn deref(&self) -> &Vec<(K, V)> {
+        &self.0
+    }
+}
+
+impl<K, V> DerefMut for MapVec<K, V> {
+    fn deref_mut(&mut self) -> &mut Vec<(K, V)> {
+        &mut self.0
+    }
+}
@@ -1,3 +1,5 @@
+use core::{fmt, ops::BitOr};
+
 const FIXSTR_SIZE: u8 = 0x1f;
 const FIXARRAY_SIZE: u8 = 0x0f;
 const FIXMAP_SIZE: u8 = 0x0f;
@@ -158,3 +160,129 @@ impl From<Marker> for u8 {
         val.to_u8()
     }
 }
+
+/// A set of kinds of msgpack values, i.e. families of [Marker]s.
+///
+/// This is used by [UnpackErr::WrongMarker](crate::UnpackErr::WrongMarker) to describe which
+/// values were expected. Kinds can be combined using `|`.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ExpectedKind(u16);
+
+impl ExpectedKind {
+    pub const NIL: ExpectedKind = ExpectedKind(1 << 0);
+    pub const BOOL: ExpectedKind = ExpectedKind(1 << 1);
+    /// An unsigned integer, i.e. a positive fixint or a `uint` marker.
+    pub const UINT: ExpectedKind = ExpectedKind(1 << 2);
+    /// A signed integer, i.e. a negative fixint or an `int` marker.
+    pub const INT: ExpectedKind = ExpectedKind(1 << 3);
+    pub const FLOAT: ExpectedKind = ExpectedKind(1 << 4);
+    pub const STR: ExpectedKind = ExpectedKind(1 << 5);
+    pub const BIN: ExpectedKind = ExpectedKind(1 << 6);
+    pub const ARRAY: ExpectedKind = ExpectedKind(1 << 7);
+    pub const MAP: ExpectedKind = ExpectedKind(1 << 8);
+    pub const EXT: ExpectedKind = ExpectedKind(1 << 9);
+    /// Any kind of value.
+    pub const ANY: ExpectedKind = ExpectedKind((1 << 10) - 1);
+
+    /// All kinds, and their names.
+    const NAMES: [(ExpectedKind, &'static str); 10] = [
+        (ExpectedKind::NIL, "nil"),
+        (ExpectedKind::BOOL, "bool"),
+        (ExpectedKind::UINT, "uint"),
+        (ExpectedKind::INT, "int"),
+        (ExpectedKind::FLOAT, "float"),
+        (ExpectedKind::STR, "str"),
+        (ExpectedKind::BIN, "bin"),
+        (ExpectedKind::ARRAY, "array"),
+        (ExpectedKind::MAP, "map"),
+        (ExpectedKind::EXT, "ext"),
+    ];
+
+    /// The empty set.
+    pub const fn empty() -> Self {
+        ExpectedKind(0)
+    }
+
+    /// Get the kind of value that starts with the given marker.
+    pub const fn of(marker: Marker) -> Self {
+        match marker {
+            Marker::Null => ExpectedKind::NIL,
+            Marker::True | Marker::False => ExpectedKind::BOOL,
+            Marker::FixPos(_) | Marker::U8 | Marker::U16 | Marker::U32 | Marker::U64 => {
+                ExpectedKind::UINT
+            }
+            Marker::FixNeg(_) | Marker::I8 | Marker::I16 | Marker::I32 | Marker::I64 => {
+                ExpectedKind::INT
+            }
+            Marker::F32 | Marker::F64 => ExpectedKind::FLOAT,
+            Marker::FixStr(_) | Marker::Str8 | Marker::Str16 | Marker::Str32 => ExpectedKind::STR,
+            Marker::Bin8 | Marker::Bin16 | Marker::Bin32 => ExpectedKind::BIN,
+            Marker::FixArray(_) | Marker::Array16 | Marker::Array32 => ExpectedKind::ARRAY,
+            Marker::FixMap(_) | Marker::Map16 | Marker::Map32 => ExpectedKind::MAP,
+            Marker::FixExt1
+            | Marker::FixExt2
+            | Marker::FixExt4
+            | Marker::FixExt8
+            | Marker::FixExt16
+            | Marker::Ext8
+            | Marker::Ext16
+            | Marker::Ext32 => ExpectedKind::EXT,
+            Marker::Reserved => ExpectedKind::empty(),
+        }
+    }
+
+    /// Returns the union of both sets.
+    pub const fn union(self, other: ExpectedKind) -> Self {
+        ExpectedKind(self.0 | other.0)
+    }
+
+    /// Returns true if all kinds in `other` are also in this set.
+    pub const fn contains(self, other: ExpectedKind) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// Returns true if the set doesn't contain any kinds.
+    pub const fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+}
+
+impl BitOr for ExpectedKind {
+    type Output = ExpectedKind;
+
+    fn bitor(self, rhs: ExpectedKind) -> ExpectedKind {
+        self.union(rhs)
+    }
+}
+
+impl fmt::Display for ExpectedKind {
+    /// Formats the set like `uint or int`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_empty() {
+            return write!(f, "nothing");
+        }
+        if *self == ExpectedKind::ANY {
+            return write!(f, "any value");
+        }
+
+        let mut names = ExpectedKind::NAMES
+            .iter()
+            .filter(|(kind, _)| self.contains(*kind))
+            .map(|(_, name)| name);
+
+        if let Some(name) = names.next() {
+            write!(f, "{name}")?;
+        }
+        for name in names {
+            write!(f, " or {name}")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Debug for ExpectedKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "ExpectedKind({self})")
+    }
+}
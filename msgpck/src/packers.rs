@@ -1,6 +1,6 @@
 //! Top-level functions for packing/unpacking types which impl [MsgPack]/[MsgUnpack].
 
-use crate::{MsgPack, MsgUnpack, PackErr, UnpackErr};
+use crate::{ContextErr, MsgPack, MsgUnpack, PackErr, UnpackCtx, UnpackErr, UnpackMode};
 
 #[cfg(feature = "alloc")]
 use alloc::{vec, vec::Vec};
@@ -30,10 +30,38 @@ pub fn pack_slice<T: MsgPack>(mut buf: &mut [u8], value: &T) -> Result<usize, Pa
 }
 
 /// Unpack a [MsgUnpack] type from a byte slice.
-pub fn unpack_slice<'a, T: MsgUnpack<'a>>(mut bytes: &'a [u8]) -> Result<T, UnpackErr> {
-    let value = T::unpack(&mut bytes)?;
-    if !bytes.is_empty() {
-        return Err(UnpackErr::TrailingBytes(bytes.len()));
-    }
-    Ok(value)
+///
+/// Numbers may be unpacked from any marker with a value that fits the target type, see
+/// [UnpackMode::Lenient]. See [unpack_slice_with_context] to also get where unpacking failed.
+pub fn unpack_slice<'a, T: MsgUnpack<'a>>(bytes: &'a [u8]) -> Result<T, UnpackErr> {
+    unpack_slice_with_context(bytes).map_err(|e| e.error)
+}
+
+/// Unpack a [MsgUnpack] type from a byte slice.
+///
+/// # Returns
+/// If unpacking fails, this returns the error along with the path to the value that failed to
+/// unpack, and the offset in `bytes` where that value starts. See [UnpackCtx::unpack_slice].
+pub fn unpack_slice_with_context<'a, T: MsgUnpack<'a>>(bytes: &'a [u8]) -> Result<T, ContextErr> {
+    UnpackCtx::new().unpack_slice(bytes)
+}
+
+/// Unpack a [MsgUnpack] type from a byte slice, in [UnpackMode::Strict] mode.
+///
+/// Numbers are only unpacked from the exact kind of marker as the target type, e.g. a signed
+/// integer isn't accepted for a `u8`, even if it isn't negative.
+pub fn unpack_slice_strict<'a, T: MsgUnpack<'a>>(bytes: &'a [u8]) -> Result<T, UnpackErr> {
+    UnpackCtx::with_mode(UnpackMode::Strict)
+        .unpack_slice(bytes)
+        .map_err(|e| e.error)
+}
+
+/// Unpack a [MsgUnpack] type from a byte slice, in [UnpackMode::Canonical] mode.
+///
+/// Like [unpack_slice_strict], but values must also be encoded canonically, so that every value
+/// has exactly one packed representation. Otherwise [UnpackErr::NonCanonical] is returned.
+pub fn unpack_slice_canonical<'a, T: MsgUnpack<'a>>(bytes: &'a [u8]) -> Result<T, UnpackErr> {
+    UnpackCtx::with_mode(UnpackMode::Canonical)
+        .unpack_slice(bytes)
+        .map_err(|e| e.error)
 }
@@ -147,4 +147,9 @@ impl<'a> Pair<'a> {
     pub fn pieces(self) -> impl Iterator<Item = Piece<'a>> {
         [Some(self.0), self.1].into_iter().flatten()
     }
+
+    /// The marker of the pair, assuming that it starts with one.
+    pub(crate) fn marker(&self) -> Marker {
+        Marker::from_u8(self.0.as_bytes()[0])
+    }
 }
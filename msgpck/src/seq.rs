@@ -0,0 +1,44 @@
+use crate::{
+    util::{pack_array, pack_array_header, write_pieces},
+    MsgPack, PackErr, Piece, Write,
+};
+
+/// A wrapper around a slice which is packed as a msgpack array.
+///
+/// [MsgPack] can't be implemented for every `[T]`, since `[u8]` is packed as bin. Wrap a slice in
+/// a `Seq` to pack it as an array instead, the same way as a `Vec<T>` or a `[T; N]`.
+///
+/// ```
+/// use msgpck::{pack_slice, Seq};
+///
+/// let mut buf = [0u8; 8];
+/// let values: &[u16] = &[1, 2, 300];
+/// let n = pack_slice(&mut buf, &Seq(values)).unwrap();
+/// assert_eq!(&buf[..n], [0x93, 0x01, 0x02, 0xcd, 0x01, 0x2c]);
+///
+/// // byte slices are packed as an array of ints too
+/// let n = pack_slice(&mut buf, &Seq(&b"hi"[..])).unwrap();
+/// assert_eq!(&buf[..n], [0x92, b'h', b'i']);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Seq<S>(pub S);
+
+impl<T: MsgPack> MsgPack for Seq<&[T]> {
+    fn pack(&self) -> impl Iterator<Item = Piece<'_>> {
+        pack_array(self.0.len(), self.0.iter())
+    }
+
+    fn pack_with_writer(&self, w: &mut dyn Write) -> Result<usize, PackErr> {
+        let mut n = write_pieces(pack_array_header(self.0.len()), w)?;
+        for elem in self.0 {
+            n += elem.pack_with_writer(w)?;
+        }
+        Ok(n)
+    }
+}
+
+impl<'a, T> From<&'a [T]> for Seq<&'a [T]> {
+    fn from(slice: &'a [T]) -> Self {
+        Seq(slice)
+    }
+}
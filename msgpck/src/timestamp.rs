@@ -0,0 +1,229 @@
+//! The msgpack timestamp extension type, which the date-time integrations are packed as.
+
+use crate::{
+    impls::ints::unpack_i64,
+    marker::{ExpectedKind, Marker},
+    util::{check_canonical, slice_take},
+    MsgPack, MsgUnpack, Piece, UnpackCtx, UnpackErr,
+};
+
+/// The ext type of msgpack timestamps.
+const TIMESTAMP_EXT: i8 = -1;
+
+/// Timestamps with more nanoseconds are invalid.
+const MAX_NANOSECONDS: u32 = 999_999_999;
+
+/// A point in time, as seconds and nanoseconds since the Unix epoch.
+///
+/// It's packed using the msgpack timestamp extension type (-1), in the smallest of the 32-, 64-
+/// and 96-bit formats that can represent it. It may also be unpacked from an integer number of
+/// seconds, except in [UnpackMode::Canonical](crate::UnpackMode::Canonical) mode.
+///
+/// ```
+/// # #[cfg(feature = "alloc")] {
+/// use msgpck::{pack_vec, unpack_slice, Timestamp};
+///
+/// let ts = Timestamp::new(1_700_000_000, 0).unwrap();
+/// let packed = pack_vec(&ts).unwrap();
+/// assert_eq!(packed, [0xd6, 0xff, 0x65, 0x53, 0xf1, 0x00]);
+/// assert_eq!(unpack_slice::<Timestamp>(&packed).unwrap(), ts);
+/// # }
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Timestamp {
+    seconds: i64,
+    nanoseconds: u32,
+}
+
+impl Timestamp {
+    /// The Unix epoch, i.e. 1970-01-01T00:00:00Z.
+    pub const UNIX_EPOCH: Timestamp = Timestamp {
+        seconds: 0,
+        nanoseconds: 0,
+    };
+
+    /// Create a timestamp from seconds since the Unix epoch, and nanoseconds within that second.
+    ///
+    /// Returns `None` if `nanoseconds` is a second or more.
+    pub const fn new(seconds: i64, nanoseconds: u32) -> Option<Self> {
+        if nanoseconds > MAX_NANOSECONDS {
+            return None;
+        }
+        Some(Timestamp {
+            seconds,
+            nanoseconds,
+        })
+    }
+
+    /// Seconds since the Unix epoch. Negative for times before it.
+    pub const fn seconds(self) -> i64 {
+        self.seconds
+    }
+
+    /// Nanoseconds within the second, always less than 1,000,000,000.
+    pub const fn nanoseconds(self) -> u32 {
+        self.nanoseconds
+    }
+}
+
+impl MsgPack for Timestamp {
+    fn pack(&self) -> impl Iterator<Item = Piece<'_>> {
+        pack_timestamp(*self)
+    }
+}
+
+impl<'buf> MsgUnpack<'buf> for Timestamp {
+    fn unpack(bytes: &mut &'buf [u8]) -> Result<Self, UnpackErr>
+    where
+        Self: Sized,
+    {
+        Self::unpack_with_ctx(bytes, &mut UnpackCtx::new())
+    }
+
+    fn unpack_with_ctx(bytes: &mut &'buf [u8], ctx: &mut UnpackCtx) -> Result<Self, UnpackErr>
+    where
+        Self: Sized,
+    {
+        let &b = bytes.first().ok_or(UnpackErr::UnexpectedEof)?;
+
+        match ExpectedKind::of(Marker::from_u8(b)) {
+            ExpectedKind::EXT => unpack_timestamp_ext(bytes, ctx),
+
+            // timestamps are always packed as ext
+            ExpectedKind::UINT | ExpectedKind::INT if ctx.is_canonical() => {
+                Err(UnpackErr::NonCanonical)
+            }
+
+            ExpectedKind::UINT | ExpectedKind::INT => Ok(Timestamp {
+                seconds: unpack_i64(bytes, ctx)?,
+                nanoseconds: 0,
+            }),
+
+            _ => Err(UnpackErr::wrong_marker(
+                Marker::from_u8(b),
+                ExpectedKind::EXT | ExpectedKind::UINT | ExpectedKind::INT,
+            )),
+        }
+    }
+}
+
+/// A timestamp, or a string which should be parsed as an RFC 3339 date-time.
+///
+/// Only the date-time integrations can parse strings, so [Timestamp] itself doesn't accept them.
+#[cfg(any(feature = "chrono", feature = "time", feature = "jiff"))]
+pub(crate) enum TimestampOrStr<'buf> {
+    Timestamp(Timestamp),
+    Str(&'buf str),
+}
+
+/// Unpack a timestamp ext, an integer number of seconds, or a string.
+///
+/// Only the timestamp ext is accepted if `ctx` is [canonical](UnpackCtx::is_canonical).
+#[cfg(any(feature = "chrono", feature = "time", feature = "jiff"))]
+pub(crate) fn unpack_timestamp_or_str<'buf>(
+    bytes: &mut &'buf [u8],
+    ctx: &mut UnpackCtx,
+) -> Result<TimestampOrStr<'buf>, UnpackErr> {
+    let &b = bytes.first().ok_or(UnpackErr::UnexpectedEof)?;
+
+    match ExpectedKind::of(Marker::from_u8(b)) {
+        // timestamps are always packed as ext
+        ExpectedKind::STR if ctx.is_canonical() => Err(UnpackErr::NonCanonical),
+
+        ExpectedKind::STR => <&str>::unpack_with_ctx(bytes, ctx).map(TimestampOrStr::Str),
+
+        _ => match Timestamp::unpack_with_ctx(bytes, ctx) {
+            Ok(ts) => Ok(TimestampOrStr::Timestamp(ts)),
+            Err(UnpackErr::WrongMarker { found, expected }) => {
+                Err(UnpackErr::wrong_marker(found, expected | ExpectedKind::STR))
+            }
+            Err(e) => Err(e),
+        },
+    }
+}
+
+/// Pack a timestamp using the smallest format that can represent it.
+pub(crate) fn pack_timestamp<'a>(ts: Timestamp) -> impl Iterator<Item = Piece<'a>> {
+    let ext_type = Some(Piece::from_i8(TIMESTAMP_EXT));
+
+    let pieces = match timestamp_marker(ts) {
+        // timestamp 32: seconds as a u32
+        m @ Marker::FixExt4 => [
+            Some(m.into()),
+            ext_type,
+            Some((ts.seconds as u32).into()),
+            None,
+            None,
+        ],
+
+        // timestamp 64: nanoseconds in the upper 30 bits, seconds in the lower 34 bits
+        m @ Marker::FixExt8 => [
+            Some(m.into()),
+            ext_type,
+            Some((u64::from(ts.nanoseconds) << 34 | ts.seconds as u64).into()),
+            None,
+            None,
+        ],
+
+        // timestamp 96: nanoseconds as a u32, followed by seconds as an i64
+        m => [
+            Some(m.into()),
+            Some(Piece::Byte(12)),
+            ext_type,
+            Some(ts.nanoseconds.into()),
+            Some(ts.seconds.into()),
+        ],
+    };
+
+    pieces.into_iter().flatten()
+}
+
+/// The marker of the smallest timestamp format that can represent `ts`.
+const fn timestamp_marker(ts: Timestamp) -> Marker {
+    match ts.seconds {
+        0..=0xffff_ffff if ts.nanoseconds == 0 => Marker::FixExt4,
+        0..=0x3_ffff_ffff => Marker::FixExt8,
+        _ => Marker::Ext8,
+    }
+}
+
+fn unpack_timestamp_ext(bytes: &mut &[u8], ctx: &UnpackCtx) -> Result<Timestamp, UnpackErr> {
+    let &[b] = slice_take(bytes)?;
+    let marker = Marker::from_u8(b);
+
+    let len: usize = match marker {
+        Marker::FixExt1 => 1,
+        Marker::FixExt2 => 2,
+        Marker::FixExt4 => 4,
+        Marker::FixExt8 => 8,
+        Marker::FixExt16 => 16,
+        Marker::Ext8 => slice_take::<u8, 1>(bytes)?[0].into(),
+        Marker::Ext16 => u16::from_be_bytes(*slice_take(bytes)?).into(),
+        Marker::Ext32 => u32::from_be_bytes(*slice_take(bytes)?).try_into()?,
+        m => return Err(UnpackErr::wrong_marker(m, ExpectedKind::EXT)),
+    };
+
+    let &[ext_type] = slice_take(bytes)?;
+    if ext_type as i8 != TIMESTAMP_EXT {
+        return Err(UnpackErr::InvalidTimestamp);
+    }
+
+    let (seconds, nanoseconds) = match len {
+        4 => (u32::from_be_bytes(*slice_take(bytes)?).into(), 0),
+        8 => {
+            let n = u64::from_be_bytes(*slice_take(bytes)?);
+            ((n & 0x3_ffff_ffff) as i64, (n >> 34) as u32)
+        }
+        12 => {
+            let nanoseconds = u32::from_be_bytes(*slice_take(bytes)?);
+            (i64::from_be_bytes(*slice_take(bytes)?), nanoseconds)
+        }
+        _ => return Err(UnpackErr::InvalidTimestamp),
+    };
+
+    let ts = Timestamp::new(seconds, nanoseconds).ok_or(UnpackErr::InvalidTimestamp)?;
+
+    check_canonical(ctx, marker, timestamp_marker(ts))?;
+
+    Ok(ts)
+}
@@ -1,6 +1,13 @@
 #![allow(dead_code)]
 
-use crate::{marker::Marker, piece::Pair, MsgPack, MsgUnpack, Piece, UnpackErr};
+#[cfg(feature = "alloc")]
+use core::marker::PhantomData;
+
+use crate::{
+    marker::{ExpectedKind, Marker},
+    piece::Pair,
+    MsgPack, MsgUnpack, PackErr, PathSegment, Piece, UnpackCtx, UnpackErr, Write,
+};
 
 pub fn slice_take<'a, T, const N: usize>(s: &mut &'a [T]) -> Result<&'a [T; N], UnpackErr> {
     if s.len() < N {
@@ -13,18 +20,54 @@ pub fn slice_take<'a, T, const N: usize>(s: &mut &'a [T]) -> Result<&'a [T; N],
     Ok(head)
 }
 
+/// Returns [UnpackErr::NonCanonical] if `ctx` is [canonical](UnpackCtx::is_canonical), and a value
+/// was unpacked from the `found` marker, but would have been packed using the `canonical` marker.
+pub(crate) fn check_canonical(
+    ctx: &UnpackCtx,
+    found: Marker,
+    canonical: Marker,
+) -> Result<(), UnpackErr> {
+    if ctx.is_canonical() && found != canonical {
+        return Err(UnpackErr::NonCanonical);
+    }
+    Ok(())
+}
+
+/// Helper function that writes msgpack [Piece]s into a [Write], and returns how many bytes were
+/// written.
+pub fn write_pieces<'a>(
+    pieces: impl Iterator<Item = Piece<'a>>,
+    w: &mut dyn Write,
+) -> Result<usize, PackErr> {
+    let mut n = 0;
+    for piece in pieces {
+        w.write_all(piece.as_bytes())?;
+        n += piece.as_bytes().len();
+    }
+    Ok(n)
+}
+
 /// Helper function that packs a msgpack array header.
 ///
 /// **NOTE**: Values of the array are not included, and must therefore be packed next.
 pub fn pack_array_header<'a>(len: usize) -> impl Iterator<Item = Piece<'a>> {
-    match len {
-        ..=0xf => Pair(Marker::FixArray(len as u8).into(), None),
-        ..=0xffff => Pair(Marker::Array16.into(), Some((len as u16).into())),
-        _ => Pair(Marker::Array32.into(), Some((len as u32).into())),
+    match array_marker(len) {
+        m @ Marker::FixArray(_) => Pair(m.into(), None),
+        m @ Marker::Array16 => Pair(m.into(), Some((len as u16).into())),
+        m => Pair(m.into(), Some((len as u32).into())),
     }
     .pieces()
 }
 
+/// The marker of an array with the given length.
+const fn array_marker(len: usize) -> Marker {
+    match len {
+        ..=0xf => Marker::FixArray(len as u8),
+        ..=0xffff => Marker::Array16,
+        _ => Marker::Array32,
+    }
+}
+
 /// Helper function that tries to decode a msgpack array header from a byte slice.
 ///
 /// **NOTE**: This doesn't decode the elements of the array, they need to be decoded next.
@@ -32,41 +75,165 @@ pub fn pack_array_header<'a>(len: usize) -> impl Iterator<Item = Piece<'a>> {
 /// ## Returns
 /// The length of the array.
 pub fn unpack_array_header(bytes: &mut &[u8]) -> Result<usize, UnpackErr> {
+    unpack_array_header_with_ctx(bytes, &UnpackCtx::new())
+}
+
+/// Like [unpack_array_header], but rejects a header that isn't in its shortest form if `ctx` is
+/// in [UnpackMode::Canonical](crate::UnpackMode::Canonical).
+pub fn unpack_array_header_with_ctx(
+    bytes: &mut &[u8],
+    ctx: &UnpackCtx,
+) -> Result<usize, UnpackErr> {
     let &[b] = slice_take(bytes)?;
+    let marker = Marker::from_u8(b);
 
-    Ok(match Marker::from_u8(b) {
+    let len = match marker {
         Marker::FixArray(len) => len.into(),
         Marker::Array16 => u16::from_be_bytes(*slice_take(bytes)?).into(),
         Marker::Array32 => u32::from_be_bytes(*slice_take(bytes)?).try_into()?,
-        m => return Err(UnpackErr::WrongMarker(m)),
-    })
+        m => return Err(UnpackErr::wrong_marker(m, ExpectedKind::ARRAY)),
+    };
+
+    check_canonical(ctx, marker, array_marker(len))?;
+
+    Ok(len)
 }
 
 /// Helper function that packs a msgpack map header.
 ///
 /// **NOTE**: Keys and values of the map are not included, and must therefore be packed next.
 pub fn pack_map_header<'a>(len: usize) -> impl Iterator<Item = Piece<'a>> {
-    match len {
-        ..=0xf => Pair(Marker::FixMap(len as u8).into(), None),
-        ..=0xffff => Pair(Marker::Map16.into(), Some((len as u16).into())),
-        _ => Pair(Marker::Map32.into(), Some((len as u32).into())),
+    match map_marker(len) {
+        m @ Marker::FixMap(_) => Pair(m.into(), None),
+        m @ Marker::Map16 => Pair(m.into(), Some((len as u16).into())),
+        m => Pair(m.into(), Some((len as u32).into())),
     }
     .pieces()
 }
 
+/// Helper function that merges the entries of two maps, which are both in the order of their keys,
+/// into a single map in the order of their keys.
+///
+/// Each piece is tagged with the key of its entry. The keys of the two maps must not overlap, so
+/// that the pieces of an entry stay together.
+pub fn merge_map_entries<'a>(
+    a: impl Iterator<Item = (u64, Piece<'a>)>,
+    b: impl Iterator<Item = (u64, Piece<'a>)>,
+) -> impl Iterator<Item = (u64, Piece<'a>)> {
+    let mut a = a.peekable();
+    let mut b = b.peekable();
+    core::iter::from_fn(move || match (a.peek(), b.peek()) {
+        (Some((key_a, _)), Some((key_b, _))) if key_b < key_a => b.next(),
+        (Some(_), _) => a.next(),
+        (None, _) => b.next(),
+    })
+}
+
+/// The marker of a map with the given length.
+const fn map_marker(len: usize) -> Marker {
+    match len {
+        ..=0xf => Marker::FixMap(len as u8),
+        ..=0xffff => Marker::Map16,
+        _ => Marker::Map32,
+    }
+}
+
 /// Helper function that tries to decode a msgpack map header from a byte slice.
 ///
 /// ## Returns
 /// The length of the map.
 pub fn unpack_map_header(bytes: &mut &[u8]) -> Result<usize, UnpackErr> {
+    unpack_map_header_with_ctx(bytes, &UnpackCtx::new())
+}
+
+/// Like [unpack_map_header], but rejects a header that isn't in its shortest form if `ctx` is in
+/// [UnpackMode::Canonical](crate::UnpackMode::Canonical).
+pub fn unpack_map_header_with_ctx(bytes: &mut &[u8], ctx: &UnpackCtx) -> Result<usize, UnpackErr> {
     let &[b] = slice_take(bytes)?;
+    let marker = Marker::from_u8(b);
 
-    Ok(match Marker::from_u8(b) {
+    let len = match marker {
         Marker::FixMap(len) => len.into(),
         Marker::Map16 => u16::from_be_bytes(*slice_take(bytes)?).into(),
         Marker::Map32 => u32::from_be_bytes(*slice_take(bytes)?).try_into()?,
-        m => return Err(UnpackErr::WrongMarker(m)),
-    })
+        m => return Err(UnpackErr::wrong_marker(m, ExpectedKind::MAP)),
+    };
+
+    check_canonical(ctx, marker, map_marker(len))?;
+
+    Ok(len)
+}
+
+/// Helper function that skips over a single msgpack value, including all the elements of arrays
+/// and maps.
+pub fn skip_value(bytes: &mut &[u8]) -> Result<(), UnpackErr> {
+    // the number of values that are left to skip
+    let mut values = 1usize;
+
+    while values > 0 {
+        values -= 1;
+
+        let &[b] = slice_take(bytes)?;
+        let len: usize = match Marker::from_u8(b) {
+            Marker::FixPos(_) | Marker::FixNeg(_) | Marker::Null | Marker::True | Marker::False => {
+                0
+            }
+            Marker::U8 | Marker::I8 => 1,
+            Marker::U16 | Marker::I16 => 2,
+            Marker::U32 | Marker::I32 | Marker::F32 => 4,
+            Marker::U64 | Marker::I64 | Marker::F64 => 8,
+            Marker::FixStr(len) => len.into(),
+            Marker::Str8 | Marker::Bin8 => slice_take::<u8, 1>(bytes)?[0].into(),
+            Marker::Str16 | Marker::Bin16 => u16::from_be_bytes(*slice_take(bytes)?).into(),
+            Marker::Str32 | Marker::Bin32 => u32::from_be_bytes(*slice_take(bytes)?).try_into()?,
+            Marker::FixArray(len) => {
+                values += usize::from(len);
+                0
+            }
+            Marker::Array16 => {
+                values += usize::from(u16::from_be_bytes(*slice_take(bytes)?));
+                0
+            }
+            Marker::Array32 => {
+                let len: usize = u32::from_be_bytes(*slice_take(bytes)?).try_into()?;
+                values = values.saturating_add(len);
+                0
+            }
+            Marker::FixMap(len) => {
+                values += 2 * usize::from(len);
+                0
+            }
+            Marker::Map16 => {
+                values += 2 * usize::from(u16::from_be_bytes(*slice_take(bytes)?));
+                0
+            }
+            Marker::Map32 => {
+                let len: usize = u32::from_be_bytes(*slice_take(bytes)?).try_into()?;
+                values = values.saturating_add(len.saturating_mul(2));
+                0
+            }
+            // the extension type is included in the length
+            Marker::FixExt1 => 2,
+            Marker::FixExt2 => 3,
+            Marker::FixExt4 => 5,
+            Marker::FixExt8 => 9,
+            Marker::FixExt16 => 17,
+            Marker::Ext8 => usize::from(slice_take::<u8, 1>(bytes)?[0]) + 1,
+            Marker::Ext16 => usize::from(u16::from_be_bytes(*slice_take(bytes)?)) + 1,
+            Marker::Ext32 => {
+                let len: usize = u32::from_be_bytes(*slice_take(bytes)?).try_into()?;
+                len.saturating_add(1)
+            }
+            m @ Marker::Reserved => return Err(UnpackErr::wrong_marker(m, ExpectedKind::ANY)),
+        };
+
+        if bytes.len() < len {
+            return Err(UnpackErr::UnexpectedEof);
+        }
+        *bytes = &bytes[len..];
+    }
+
+    Ok(())
 }
 
 pub fn pack_map<'a, K, V>(
@@ -80,13 +247,61 @@ where
     pack_map_header(len).chain(kvs.flat_map(|(k, v)| k.pack().chain(v.pack())))
 }
 
-pub fn unpack_map<'a, K, V, C>(bytes: &mut &'a [u8]) -> Result<C, UnpackErr>
+/// Helper function that packs a map with the entries sorted by the packed bytes of their keys.
+///
+/// Unlike [pack_map], the packed bytes don't depend on the iteration order of the map, which makes
+/// this useful for maps without a defined order, like `HashMap`.
+#[cfg(feature = "alloc")]
+pub fn pack_map_sorted<'a, K, V>(
+    kvs: impl Iterator<Item = (&'a K, &'a V)>,
+) -> impl Iterator<Item = Piece<'a>> + 'a
+where
+    K: MsgPack + 'a,
+    V: MsgPack + 'a,
+{
+    let mut entries: alloc::vec::Vec<_> = kvs.map(|(k, v)| (packed_bytes(k), k, v)).collect();
+    entries.sort_unstable_by(|(a, ..), (b, ..)| a.cmp(b));
+
+    pack_map_header(entries.len()).chain(
+        entries
+            .into_iter()
+            .flat_map(|(_, k, v)| k.pack().chain(v.pack())),
+    )
+}
+
+/// Helper function that packs an array with the elements sorted by their packed bytes.
+///
+/// Like [pack_map_sorted], but for sets without a defined order, like `HashSet`.
+#[cfg(feature = "alloc")]
+pub fn pack_array_sorted<'a, T>(
+    elements: impl Iterator<Item = &'a T>,
+) -> impl Iterator<Item = Piece<'a>> + 'a
+where
+    T: MsgPack + 'a,
+{
+    let mut elements: alloc::vec::Vec<_> =
+        elements.map(|elem| (packed_bytes(elem), elem)).collect();
+    elements.sort_unstable_by(|(a, _), (b, _)| a.cmp(b));
+
+    pack_array_header(elements.len()).chain(elements.into_iter().flat_map(|(_, elem)| elem.pack()))
+}
+
+#[cfg(feature = "alloc")]
+fn packed_bytes<T: MsgPack>(value: &T) -> alloc::vec::Vec<u8> {
+    let mut packed = alloc::vec::Vec::new();
+    for piece in value.pack() {
+        packed.extend_from_slice(piece.as_bytes());
+    }
+    packed
+}
+
+pub fn unpack_map<'a, K, V, C>(bytes: &mut &'a [u8], ctx: &mut UnpackCtx) -> Result<C, UnpackErr>
 where
     K: MsgUnpack<'a>,
     V: MsgUnpack<'a>,
     C: FromIterator<(K, V)>,
 {
-    let len = unpack_map_header(bytes)?;
+    let len = unpack_map_header_with_ctx(bytes, ctx)?;
 
     // sanity check
     // make sure that it's plausible the array could contain this many elements
@@ -94,15 +309,93 @@ where
         return Err(UnpackErr::UnexpectedEof);
     }
 
+    let mut prev_key = None;
     (0..len)
-        .map(move |_| {
-            let k = K::unpack(bytes)?;
-            let v = V::unpack(bytes)?;
-            Ok((k, v))
-        })
+        .map(move |i| unpack_map_entry(bytes, ctx, &mut prev_key, i))
         .collect()
 }
 
+/// Helper function that unpacks the entries of a msgpack map one at a time, and inserts them into
+/// `map` using `insert`.
+///
+/// This is useful for fixed-capacity maps. If `insert` gives the entry back because the map is
+/// full, [UnpackErr::BufferOverflow] is returned.
+pub fn unpack_map_with<'a, K, V, M>(
+    bytes: &mut &'a [u8],
+    ctx: &mut UnpackCtx,
+    mut map: M,
+    insert: impl Fn(&mut M, K, V) -> Result<(), (K, V)>,
+) -> Result<M, UnpackErr>
+where
+    K: MsgUnpack<'a>,
+    V: MsgUnpack<'a>,
+{
+    let len = unpack_map_header_with_ctx(bytes, ctx)?;
+
+    let mut prev_key = None;
+    for i in 0..len {
+        let (k, v) = unpack_map_entry(bytes, ctx, &mut prev_key, i)?;
+        insert(&mut map, k, v).map_err(|_| UnpackErr::BufferOverflow)?;
+    }
+
+    Ok(map)
+}
+
+/// Unpack entry number `i` of a map, where `prev_key` holds the packed key of the previous entry.
+fn unpack_map_entry<'a, K, V>(
+    bytes: &mut &'a [u8],
+    ctx: &mut UnpackCtx,
+    prev_key: &mut Option<&'a [u8]>,
+    i: usize,
+) -> Result<(K, V), UnpackErr>
+where
+    K: MsgUnpack<'a>,
+    V: MsgUnpack<'a>,
+{
+    let start = *bytes;
+    let k = unpack_map_key(bytes, ctx, prev_key)
+        .map_err(|e| ctx.error_at(PathSegment::Index(i), start, e))?;
+    let start = *bytes;
+    let v = V::unpack_with_ctx(bytes, ctx)
+        .map_err(|e| ctx.error_at(PathSegment::Index(i), start, e))?;
+    Ok((k, v))
+}
+
+/// Helper function that unpacks the key of a map entry, where `prev_key` holds the packed key of
+/// the previous entry, if any.
+///
+/// If `ctx` is [canonical](UnpackCtx::is_canonical), the packed keys must be strictly increasing,
+/// i.e. sorted by their packed bytes without duplicates. Otherwise [UnpackErr::NonCanonical] is
+/// returned.
+pub fn unpack_map_key<'a, K: MsgUnpack<'a>>(
+    bytes: &mut &'a [u8],
+    ctx: &mut UnpackCtx,
+    prev_key: &mut Option<&'a [u8]>,
+) -> Result<K, UnpackErr> {
+    let key_start: &'a [u8] = bytes;
+    let key = K::unpack_with_ctx(bytes, ctx)?;
+    let packed_key = &key_start[..key_start.len() - bytes.len()];
+
+    if ctx.is_canonical() && prev_key.is_some_and(|prev| prev >= packed_key) {
+        return Err(UnpackErr::NonCanonical);
+    }
+    *prev_key = Some(packed_key);
+
+    Ok(key)
+}
+
+/// Helper function that unpacks a value using `f`, and adds the name of the type to the path of any
+/// error, see [UnpackCtx::error_at].
+pub fn unpack_with_type<'buf, T>(
+    type_name: &'static str,
+    bytes: &mut &'buf [u8],
+    ctx: &mut UnpackCtx,
+    f: impl FnOnce(&mut &'buf [u8], &mut UnpackCtx) -> Result<T, UnpackErr>,
+) -> Result<T, UnpackErr> {
+    let start = *bytes;
+    f(bytes, ctx).map_err(|e| ctx.error_at(PathSegment::Type(type_name), start, e))
+}
+
 pub fn pack_array<'a, T>(
     len: usize,
     elements: impl Iterator<Item = &'a T> + 'a,
@@ -112,13 +405,98 @@ where
 {
     pack_array_header(len).chain(elements.flat_map(|elem| elem.pack()))
 }
-pub fn unpack_array<'a, T, C>(bytes: &mut &'a [u8]) -> Result<C, UnpackErr>
+pub fn unpack_array<'a, T, C>(bytes: &mut &'a [u8], ctx: &mut UnpackCtx) -> Result<C, UnpackErr>
 where
     T: MsgUnpack<'a>,
     C: FromIterator<T>,
 {
-    let len = unpack_array_header(bytes)?;
-    (0..len).map(move |_| T::unpack(bytes)).collect()
+    let len = unpack_array_header_with_ctx(bytes, ctx)?;
+    (0..len)
+        .map(move |i| {
+            let start = *bytes;
+            T::unpack_with_ctx(bytes, ctx)
+                .map_err(|e| ctx.error_at(PathSegment::Index(i), start, e))
+        })
+        .collect()
+}
+
+/// Helper function that unpacks the elements of a msgpack array one at a time, and adds them to
+/// `container` using `push`.
+///
+/// This is useful for fixed-capacity containers. If `push` gives the element back because the
+/// container is full, [UnpackErr::BufferOverflow] is returned.
+pub fn unpack_array_with<'a, T, C>(
+    bytes: &mut &'a [u8],
+    ctx: &mut UnpackCtx,
+    mut container: C,
+    push: impl Fn(&mut C, T) -> Result<(), T>,
+) -> Result<C, UnpackErr>
+where
+    T: MsgUnpack<'a>,
+{
+    let len = unpack_array_header_with_ctx(bytes, ctx)?;
+    for i in 0..len {
+        let start = *bytes;
+        let elem = T::unpack_with_ctx(bytes, ctx)
+            .map_err(|e| ctx.error_at(PathSegment::Index(i), start, e))?;
+        push(&mut container, elem).map_err(|_| UnpackErr::BufferOverflow)?;
+    }
+
+    Ok(container)
+}
+
+/// An iterator over the [Piece]s of an owned value.
+///
+/// A [Piece] may borrow from the value it was packed from, which means that the pieces of a
+/// temporary value can't outlive it. This iterator packs the value once into a buffer instead, and
+/// copies the bytes out in pieces of up to 8 bytes.
+///
+/// This is used by the derive macro for `#[msgpck(into = "T")]`.
+#[cfg(feature = "alloc")]
+pub struct PackOwned<'a> {
+    packed: alloc::vec::Vec<u8>,
+
+    /// How many bytes that already have been copied out.
+    offset: usize,
+
+    /// The pieces don't actually borrow anything, but the iterator must be able to produce pieces
+    /// of any lifetime.
+    _pieces: PhantomData<Piece<'a>>,
+}
+
+#[cfg(feature = "alloc")]
+impl PackOwned<'_> {
+    pub fn new<T: MsgPack>(value: T) -> Self {
+        let mut packed = alloc::vec::Vec::new();
+        for piece in value.pack() {
+            packed.extend_from_slice(piece.as_bytes());
+        }
+
+        PackOwned {
+            packed,
+            offset: 0,
+            _pieces: PhantomData,
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'a> Iterator for PackOwned<'a> {
+    type Item = Piece<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let bytes = &self.packed[self.offset..];
+        let piece = match bytes.len() {
+            0 => return None,
+            8.. => Piece::Bytes8(bytes[..8].try_into().expect("slice is 8 bytes")),
+            4.. => Piece::Bytes4(bytes[..4].try_into().expect("slice is 4 bytes")),
+            2.. => Piece::Bytes2(bytes[..2].try_into().expect("slice is 2 bytes")),
+            _ => Piece::Byte(bytes[0]),
+        };
+
+        self.offset += piece.as_bytes().len();
+        Some(piece)
+    }
 }
 
 pub enum Either<A, B> {
@@ -1,8 +1,8 @@
 use core::fmt;
-use std::{collections::HashSet, fmt::Display};
+use std::{collections::HashSet, fmt::Display, str::FromStr};
 
 use strum::{EnumIter, IntoEnumIterator};
-use syn::meta::ParseNestedMeta;
+use syn::{meta::ParseNestedMeta, parse::Parse, spanned::Spanned, Fields, Lit};
 
 use crate::DeriveKind;
 
@@ -24,8 +24,110 @@ pub enum Attribute {
     ///
     /// Same as `#[serde(skip)]`
     Skip,
+
+    /// Pack and unpack this field using the functions in the given module, which must look like:
+    ///
+    /// ```ignore
+    /// fn pack(value: &T) -> impl Iterator<Item = msgpck::Piece<'_>>;
+    /// fn pack_with_writer(value: &T, w: &mut dyn msgpck::Write) -> Result<usize, msgpck::PackErr>;
+    /// fn unpack<'buf>(bytes: &mut &'buf [u8]) -> Result<T, msgpck::UnpackErr>;
+    /// ```
+    ///
+    /// Same as `#[serde(with = "module")]`
+    With(AttrValue),
+
+    /// Pack this field using the given function instead of [MsgPack]. The function must look like:
+    ///
+    /// ```ignore
+    /// fn pack(value: &T) -> impl Iterator<Item = msgpck::Piece<'_>>;
+    /// ```
+    ///
+    /// The pieces returned by the function are also used by `MsgPack::pack_with_writer`.
+    PackWith(AttrValue),
+
+    /// Unpack this field using the given function instead of [MsgUnpack]. The function must look
+    /// like:
+    ///
+    /// ```ignore
+    /// fn unpack<'buf>(bytes: &mut &'buf [u8]) -> Result<T, msgpck::UnpackErr>;
+    /// ```
+    UnpackWith(AttrValue),
+
+    /// Pack this field as bin instead of as an array of integers, and unpack it from either. The
+    /// field must be a byte container like `Vec<u8>`, `heapless::Vec<u8, N>` or `[u8; N]`.
+    ///
+    /// Same as `#[serde(with = "serde_bytes")]`
+    Bytes,
+
+    /// Pack and unpack a struct with a single field exactly like that field.
+    ///
+    /// Same as `#[serde(transparent)]`
+    Transparent,
+
+    /// Unpack the type by unpacking the given type and converting it using `From`.
+    ///
+    /// Same as `#[serde(from = "T")]`
+    From(AttrValue),
+
+    /// Unpack the type by unpacking the given type and converting it using `TryFrom`. The error of
+    /// the conversion must impl `Display`.
+    ///
+    /// Same as `#[serde(try_from = "T")]`
+    TryFrom(AttrValue),
+
+    /// Pack the type by cloning it, converting it into the given type using `Into`, and packing
+    /// that. `MsgPack::pack` packs the converted value into a buffer, and therefore requires the
+    /// `alloc` feature.
+    ///
+    /// Same as `#[serde(into = "T")]`
+    Into(AttrValue),
+
+    /// Replace the trait bounds that are generated for the type parameters with the given
+    /// where-predicates. When unpacking, the lifetime of the buffer is called `'_msgpck`.
+    ///
+    /// Use `#[msgpck(pack(bound = "..."))]` and `#[msgpck(unpack(bound = "..."))]` to set different
+    /// bounds for the two derives.
+    ///
+    /// Same as `#[serde(bound = "...")]`
+    Bound(AttrValue),
+
+    /// Pack/unpack the fields of this struct field as if they were fields of the outer struct.
+    ///
+    /// In a struct with `#[msgpck(key = N)]`, the flattened field doesn't have a key, and must be
+    /// a struct with keys itself. Its entries are packed before the other fields of the map.
+    ///
+    /// Same as `#[serde(flatten)]`
+    Flatten,
+
+    /// Pack the struct as a map, using the given integer as the key of this field instead of its
+    /// name. Fields may be unpacked in any order, unknown keys are skipped, and missing fields are
+    /// set to `Default::default()`. Fields are packed in the order of their keys.
+    ///
+    /// If one field of a struct has a key, all (non-skipped, non-flattened) fields must have one.
+    Key(AttrValue),
+
+    /// Leave this field out of the packed map if the given function returns `true`. The function
+    /// must look like `fn(&T) -> bool`. Only supported for structs with `#[msgpck(key = N)]`,
+    /// which set missing fields to `Default::default()` when unpacking.
+    ///
+    /// Same as `#[serde(skip_serializing_if = "path")]`
+    SkipIf(AttrValue),
+
+    /// Validate the value after unpacking it, using the given function. The function must look
+    /// like:
+    ///
+    /// ```ignore
+    /// fn validate(value: &T) -> Result<(), &'static str>;
+    /// ```
+    ///
+    /// An error is returned from `MsgUnpack::unpack` as `UnpackErr::Invalid`.
+    Validate(AttrValue),
 }
 
+/// The value of an attribute that is declared as `name = "value"`.
+#[derive(Clone, Default, PartialEq, Eq, Hash)]
+pub struct AttrValue(Option<Lit>);
+
 #[derive(Clone, Copy, PartialEq, Eq)]
 #[allow(dead_code)]
 pub enum AttrLocation {
@@ -39,12 +141,67 @@ pub enum AttrLocation {
 impl Attribute {
     /// Get the "name" of the attribute, i.e. the exact string that is used when declaring the
     /// attribute.
-    fn name(&self) -> &'static str {
+    pub fn name(&self) -> &'static str {
         match self {
             Attribute::Untagged => "untagged",
             Attribute::Other => "other",
             Attribute::Default => "default",
             Attribute::Skip => "skip",
+            Attribute::With(_) => "with",
+            Attribute::PackWith(_) => "pack_with",
+            Attribute::UnpackWith(_) => "unpack_with",
+            Attribute::Bytes => "bytes",
+            Attribute::Transparent => "transparent",
+            Attribute::From(_) => "from",
+            Attribute::TryFrom(_) => "try_from",
+            Attribute::Into(_) => "into",
+            Attribute::Bound(_) => "bound",
+            Attribute::Flatten => "flatten",
+            Attribute::Key(_) => "key",
+            Attribute::SkipIf(_) => "skip_if",
+            Attribute::Validate(_) => "validate",
+        }
+    }
+
+    /// Get the value of the attribute, if it's declared as `name = "value"`.
+    pub fn value(&self) -> Option<&AttrValue> {
+        match self {
+            Attribute::With(value)
+            | Attribute::PackWith(value)
+            | Attribute::UnpackWith(value)
+            | Attribute::From(value)
+            | Attribute::TryFrom(value)
+            | Attribute::Into(value)
+            | Attribute::Bound(value)
+            | Attribute::Key(value)
+            | Attribute::SkipIf(value)
+            | Attribute::Validate(value) => Some(value),
+
+            Attribute::Untagged
+            | Attribute::Other
+            | Attribute::Default
+            | Attribute::Skip
+            | Attribute::Bytes
+            | Attribute::Transparent
+            | Attribute::Flatten => None,
+        }
+    }
+
+    /// Set the value of an attribute that is declared as `name = "value"`.
+    fn set_value(&mut self, lit: Lit) {
+        match self {
+            Attribute::With(value)
+            | Attribute::PackWith(value)
+            | Attribute::UnpackWith(value)
+            | Attribute::From(value)
+            | Attribute::TryFrom(value)
+            | Attribute::Into(value)
+            | Attribute::Bound(value)
+            | Attribute::Key(value)
+            | Attribute::SkipIf(value)
+            | Attribute::Validate(value) => value.0 = Some(lit),
+
+            _ => unreachable!("attribute doesn't take a value"),
         }
     }
 
@@ -64,6 +221,35 @@ impl Attribute {
             (Attribute::Default, MsgUnpack) => matches!(location, EnumVariantField | StructField),
 
             (Attribute::Skip, _) => matches!(location, EnumVariantField | StructField),
+
+            // pack_with and unpack_with are allowed for both derives, since they are usually used
+            // together on a type that derives both traits.
+            (
+                Attribute::With(_)
+                | Attribute::PackWith(_)
+                | Attribute::UnpackWith(_)
+                | Attribute::Bytes,
+                _,
+            ) => matches!(location, EnumVariantField | StructField),
+
+            (Attribute::Transparent, _) => matches!(location, Struct),
+
+            // Like above, the conversion attributes are allowed for both derives.
+            (Attribute::From(_) | Attribute::TryFrom(_) | Attribute::Into(_), _) => {
+                matches!(location, Struct | Enum)
+            }
+
+            (Attribute::Bound(_), _) => matches!(location, Struct | Enum),
+
+            (Attribute::Flatten | Attribute::Key(_), _) => matches!(location, StructField),
+
+            // skip_if only affects packing, but is allowed for both derives, like pack_with
+            (Attribute::SkipIf(_), _) => matches!(location, StructField),
+
+            // and validate only affects unpacking
+            (Attribute::Validate(_), _) => {
+                matches!(location, Struct | Enum | StructField | EnumVariantField)
+            }
         }
     }
 }
@@ -85,7 +271,7 @@ pub fn parse_attributes(
     location: AttrLocation,
     kind: DeriveKind,
 ) -> syn::Result<HashSet<Attribute>> {
-    let mut attributes = HashSet::new();
+    let mut attributes: HashSet<Attribute> = HashSet::new();
     for attr in attrs {
         if !attr.path().is_ident("msgpck") {
             continue;
@@ -109,7 +295,7 @@ pub fn parse_attributes(
 
             let mut parse_arg_meta = |meta: ParseNestedMeta| {
                 let mut all_attributes = Attribute::iter();
-                let attribute = loop {
+                let mut attribute = loop {
                     let Some(attribute) = all_attributes.next() else {
                         return Err(meta.error("unexpected attribute"));
                     };
@@ -119,12 +305,26 @@ pub fn parse_attributes(
                     }
                 };
 
-                if check_attribute && !attribute.is_supported_at(location, kind) {
+                if attribute.value().is_some() {
+                    attribute.set_value(meta.value()?.parse()?);
+                }
+
+                // attributes that are filtered for another derive kind are ignored
+                if !check_attribute {
+                    return Ok(());
+                }
+
+                if !attribute.is_supported_at(location, kind) {
                     return Err(meta.error(format!(
                         "this attribute isn't supported by {kind:?} on item \"{location}\""
                     )));
                 }
 
+                let name = attribute.name();
+                if attribute.value().is_some() && attributes.iter().any(|a| a.name() == name) {
+                    return Err(meta.error(format!("duplicate attribute \"{name}\"")));
+                }
+
                 attributes.insert(attribute);
 
                 Ok(())
@@ -142,3 +342,124 @@ pub fn parse_attributes(
 
     Ok(attributes)
 }
+
+/// Parse the value of a `name = "value"` attribute, if it is present.
+///
+/// `variant` is the attribute variant to look for, e.g. `Attribute::With`.
+pub fn parse_value<T: Parse>(
+    attributes: &HashSet<Attribute>,
+    variant: fn(AttrValue) -> Attribute,
+) -> syn::Result<Option<T>> {
+    let name = variant(AttrValue::default()).name();
+    let Some(value) = attributes
+        .iter()
+        .find(|a| a.name() == name)
+        .and_then(Attribute::value)
+    else {
+        return Ok(None);
+    };
+
+    match &value.0 {
+        Some(Lit::Str(s)) => s.parse().map(Some),
+        Some(lit) => Err(syn::Error::new(
+            lit.span(),
+            format!("expected a string: {name} = \"...\""),
+        )),
+        None => unreachable!("value is set by parse_attributes"),
+    }
+}
+
+/// Parse the value of a `name = 123` attribute, if it is present.
+///
+/// `variant` is the attribute variant to look for, e.g. `Attribute::Key`.
+pub fn parse_int_value<N>(
+    attributes: &HashSet<Attribute>,
+    variant: fn(AttrValue) -> Attribute,
+) -> syn::Result<Option<N>>
+where
+    N: FromStr,
+    N::Err: Display,
+{
+    let name = variant(AttrValue::default()).name();
+    let Some(value) = attributes
+        .iter()
+        .find(|a| a.name() == name)
+        .and_then(Attribute::value)
+    else {
+        return Ok(None);
+    };
+
+    match &value.0 {
+        Some(Lit::Int(i)) => i.base10_parse().map(Some),
+        Some(lit) => Err(syn::Error::new(
+            lit.span(),
+            format!("expected an integer: {name} = 123"),
+        )),
+        None => unreachable!("value is set by parse_attributes"),
+    }
+}
+
+/// Get the `#[msgpck(key = N)]` of every field of a struct, or `None` if the fields don't have keys.
+///
+/// Skipped and flattened fields are `None` in the returned list. Returns an error if only some of
+/// the fields have keys, or if two fields have the same key.
+pub fn parse_field_keys(
+    fields: &Fields,
+    kind: DeriveKind,
+) -> syn::Result<Option<Vec<Option<u64>>>> {
+    let mut keys = Vec::new();
+    let mut unique_keys = HashSet::new();
+
+    // a field without a key, which isn't allowed if any other field has one
+    let mut unkeyed_field = None;
+
+    for field in fields {
+        let attributes = parse_attributes(&field.attrs, AttrLocation::StructField, kind)?;
+        if attributes.contains(&Attribute::Skip) {
+            keys.push(None);
+            continue;
+        }
+
+        let key: Option<u64> = parse_int_value(&attributes, Attribute::Key)?;
+
+        // the entries of a flattened field have their own keys
+        if attributes.contains(&Attribute::Flatten) {
+            if key.is_some() {
+                return Err(syn::Error::new(
+                    field.span(),
+                    "#[msgpck(flatten)] can't be combined with #[msgpck(key)]",
+                ));
+            }
+
+            keys.push(None);
+            continue;
+        }
+
+        match key {
+            Some(key) => {
+                if !unique_keys.insert(key) {
+                    return Err(syn::Error::new(
+                        field.span(),
+                        format!("duplicate key {key}"),
+                    ));
+                }
+            }
+            None => unkeyed_field = unkeyed_field.or(Some(field.span())),
+        }
+
+        keys.push(key);
+    }
+
+    if unique_keys.is_empty() {
+        return Ok(None);
+    }
+
+    if let Some(span) = unkeyed_field {
+        return Err(syn::Error::new(
+            span,
+            "all fields must have a #[msgpck(key)] if any field has one",
+        ));
+    }
+
+    Ok(Some(keys))
+}
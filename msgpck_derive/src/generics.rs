@@ -0,0 +1,69 @@
+use std::collections::HashSet;
+
+use syn::{
+    parse::{Parse, ParseStream},
+    parse_quote,
+    punctuated::Punctuated,
+    DeriveInput, Generics, LifetimeParam, Token, WherePredicate,
+};
+
+use crate::{
+    attribute::{parse_value, Attribute},
+    DeriveKind,
+};
+
+/// The where-predicates of a `#[msgpck(bound = "...")]` attribute.
+struct Bounds(Punctuated<WherePredicate, Token![,]>);
+
+impl Parse for Bounds {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        Punctuated::parse_terminated(input).map(Bounds)
+    }
+}
+
+/// Generate the generics of a derived impl.
+///
+/// Every type parameter is bounded by the derived trait, unless the bounds are overridden using
+/// `#[msgpck(bound = "...")]`. For [DeriveKind::MsgUnpack] a `'_msgpck` lifetime, which outlives all
+/// other lifetimes, is added first.
+///
+/// Use the generics of the [DeriveInput] for the type, and these ones for the impl.
+pub fn impl_generics(
+    input: &DeriveInput,
+    attributes: &HashSet<Attribute>,
+    kind: DeriveKind,
+) -> syn::Result<Generics> {
+    let mut generics = input.generics.clone();
+    let bounds: Option<Bounds> = parse_value(attributes, Attribute::Bound)?;
+
+    let where_clause = generics.make_where_clause();
+    match bounds {
+        Some(Bounds(predicates)) => where_clause.predicates.extend(predicates),
+        None => {
+            for t in input.generics.type_params() {
+                let t = &t.ident;
+                where_clause.predicates.push(match kind {
+                    DeriveKind::MsgPack => parse_quote! { #t: ::msgpck::MsgPack },
+                    DeriveKind::MsgUnpack => parse_quote! { #t: ::msgpck::MsgUnpack<'_msgpck> },
+                });
+            }
+        }
+    }
+
+    if kind == DeriveKind::MsgUnpack {
+        add_msgpck_lifetime(&mut generics);
+    }
+
+    Ok(generics)
+}
+
+/// Add the `'_msgpck` lifetime of the buffer that is unpacked from, which outlives all other
+/// lifetimes since the unpacked value may borrow from the buffer.
+pub fn add_msgpck_lifetime(generics: &mut Generics) {
+    let mut msgpck_lifetime: LifetimeParam = parse_quote! { '_msgpck };
+    msgpck_lifetime.bounds = generics.lifetimes().map(|l| l.lifetime.clone()).collect();
+    if !msgpck_lifetime.bounds.is_empty() {
+        msgpck_lifetime.colon_token = Some(Default::default());
+    }
+    generics.params.insert(0, msgpck_lifetime.into());
+}
@@ -3,6 +3,7 @@
 extern crate proc_macro;
 
 mod attribute;
+mod generics;
 mod pack;
 mod unpack;
 
@@ -82,3 +83,22 @@ fn array_len_write(len: usize) -> TokenStream {
         }
     }
 }
+
+/// Generate the `KEYS` of a keyed struct, from the keys of its fields and the `KEYS` of its
+/// flattened fields, which fails to compile if any key is used more than once.
+fn map_keys<'a>(
+    keys: impl Iterator<Item = &'a u64>,
+    flattened_keys: &[TokenStream],
+) -> TokenStream {
+    quote! {{
+        let keys = ::msgpck::helpers::MapKeys {
+            keys: &[#(#keys),*],
+            flattened: &[#(#flattened_keys),*],
+        };
+        ::core::assert!(
+            keys.is_unique(),
+            "the keys of a struct and its flattened fields must be unique",
+        );
+        keys
+    }}
+}
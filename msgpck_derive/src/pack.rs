@@ -1,24 +1,53 @@
+use std::collections::HashSet;
+
 use proc_macro2::{Ident, TokenStream};
 use quote::{quote, TokenStreamExt};
-use syn::{spanned::Spanned, Fields};
+use syn::{parse_quote, spanned::Spanned, DeriveInput, Fields, Path, Type};
 
 use crate::{
     array_len_write,
-    attribute::{parse_attributes, AttrLocation, Attribute},
-    DeriveKind, RESERVED_NAMES,
+    attribute::{parse_attributes, parse_field_keys, parse_value, AttrLocation, Attribute},
+    map_keys, DeriveKind, RESERVED_NAMES,
 };
 
 pub mod enums;
 pub mod structs;
 
 pub struct PackFields {
+    /// ```ignore
+    ///     .chain(::msgpck::helpers::pack_array_header(3))
+    /// // or nothing, in the case of a newtype
+    /// ```
+    pub pack_header: TokenStream,
+
     /// ```ignore
     ///     .chain(field1.pack())
     ///     .chain(field2.pack())
     ///     .chain(field3.pack())
+    /// // or nothing, in the case of keyed fields
     /// ```
     pub pack_fields: TokenStream,
 
+    /// An iterator of the map entries of keyed fields, in the order of their keys, where each
+    /// piece is tagged with the key of its entry. It must be evaluated after
+    /// [PackFields::skip_fields].
+    /// ```ignore
+    /// ::msgpck::helpers::merge_map_entries(
+    ///     ::core::iter::empty()
+    ///         .chain(pack_u64(0).chain(field1.pack()).map(|piece| (0, piece)))
+    ///         .chain(pack_u64(2).chain(field3.pack()).map(|piece| (2, piece))),
+    ///     __msgpck_pieces_field2,
+    /// )
+    /// ```
+    pub map_entries: TokenStream,
+
+    /// The value of [PackMapFields::KEYS](::msgpck::helpers::PackMapFields::KEYS) for keyed
+    /// fields.
+    pub map_keys: TokenStream,
+
+    /// Packs the array header to `__msgpck_w`, like [PackFields::pack_header].
+    pub write_pack_header: TokenStream,
+
     /// ```ignore
     /// __msgpck_n += field1,pack_with_writer(__msgpck_w)?;
     /// __msgpck_n += field2,pack_with_writer(__msgpck_w)?;
@@ -28,129 +57,372 @@ pub struct PackFields {
 
     /// ```ignore
     /// // either
-    ///     { field1, field2, field3 }
+    ///     { field1, field2, field3, .. }
     /// // or
-    ///     ( field1, field2, field3 )
+    ///     ( field1, _, field3 )
     /// // or nothing, in the case of an item without fields, brackets, and parentheses
     /// ```
     pub match_fields: TokenStream,
 
+    /// Evaluates the `#[msgpck(skip_if)]` predicates once, after matching the fields, and counts
+    /// the entries of flattened keyed structs.
+    /// ```ignore
+    /// let __msgpck_skip_field1 = is_default(field1);
+    /// let (__msgpck_len_field2, __msgpck_pieces_field2) = PackMapFields::pack_map_fields(field2);
+    /// ```
+    pub skip_fields: TokenStream,
+
+    /// An expression for the number of packed fields, including flattened ones.
+    pub fields_len: TokenStream,
+
+    /// An expression for the number of packed map entries of keyed fields, which must be evaluated
+    /// after [PackFields::skip_fields].
+    pub map_len: TokenStream,
+
     /// `true` the item contains no fields.
     pub unit: bool,
+
+    /// `true` if the fields are packed as a map with integer keys, i.e. using `#[msgpck(key = N)]`.
+    pub keyed: bool,
 }
 
 /// Pack a set of fields, i.e. a struct or the fields of an enum variant.
 pub fn pack_fields(fields: &Fields, location: AttrLocation) -> syn::Result<PackFields> {
-    let unit;
     let mut pack_fields = quote! {};
     let mut write_pack_fields = quote! {};
     let mut match_fields = quote! {};
+    let mut skip_fields = quote! {};
 
-    match fields {
-        Fields::Named(fields) => {
-            let mut fields_len = 0usize;
-            for field in fields.named.iter() {
-                let attributes = parse_attributes(&field.attrs, location, DeriveKind::MsgPack)?;
-                if !attributes.contains(&Attribute::Skip) {
-                    fields_len += 1;
-                }
-            }
+    // the number of fields that aren't skipped or flattened
+    let mut fields_len = 0usize;
 
-            pack_fields.append_all(quote! {
-                .chain(::msgpck::helpers::pack_array_header(#fields_len))
-            });
-            write_pack_fields.append_all(array_len_write(fields_len));
+    // the number of fields in flattened structs, which isn't known until compile time
+    let mut flattened_len = quote! {};
 
-            unit = fields_len == 0;
+    // the number of fields skipped by #[msgpck(skip_if)], which isn't known until runtime
+    let mut skipped_len = quote! {};
 
-            for field in &fields.named {
-                let field_attributes =
-                    parse_attributes(&field.attrs, location, DeriveKind::MsgPack)?;
-                if field_attributes.contains(&Attribute::Skip) {
-                    continue;
-                }
+    // the number of entries in flattened keyed structs, which isn't known until runtime either
+    let mut flattened_map_len = quote! {};
+
+    // the tagged map entries and the keys of flattened keyed structs
+    let mut flattened_entries = vec![];
+    let mut flattened_keys = vec![];
 
-                let field_name = field.ident.as_ref().expect("fields are named");
+    // the keys of all fields, if the fields are packed as a map
+    let keys = match location {
+        AttrLocation::StructField => parse_field_keys(fields, DeriveKind::MsgPack)?,
+        _ => None,
+    };
+    let keyed = keys.is_some();
 
+    // the key, pack and write_pack of each field that isn't skipped
+    let mut packed_fields = vec![];
+
+    for (i, field) in fields.iter().enumerate() {
+        let field_attributes = parse_attributes(&field.attrs, location, DeriveKind::MsgPack)?;
+        if field_attributes.contains(&Attribute::Skip) {
+            if field.ident.is_none() {
+                match_fields.append_all(quote! { _, });
+            }
+            continue;
+        }
+
+        let field_name = match &field.ident {
+            Some(field_name) => {
                 if RESERVED_NAMES.contains(&field_name.to_string().as_str()) {
                     return Err(syn::Error::new(
                         field.ident.span(),
                         "MsgPack: reserved identifier",
                     ));
                 }
+                field_name.clone()
+            }
+            None => Ident::new(&format!("_{i}"), field.span()),
+        };
 
-                // pattern match all the fields
-                match_fields.append_all(quote! {#field_name, });
+        // pattern match all the fields
+        match_fields.append_all(quote! {#field_name, });
 
-                // pack all the named fields
-                pack_fields.append_all(quote! {
-                    .chain(::msgpck::MsgPack::pack(#field_name))
-                });
+        // the entries of flattened keyed structs are merged with the other entries below
+        if keyed && field_attributes.contains(&Attribute::Flatten) {
+            check_flatten_field(&field_name, &field_attributes)?;
+            let ty = &field.ty;
+            flattened_keys.push(quote! { <#ty as ::msgpck::helpers::PackMapFields>::KEYS });
+            flattened_entries.push(flatten_map_field(
+                &field_name,
+                &mut skip_fields,
+                &mut flattened_map_len,
+            ));
+            continue;
+        }
 
-                write_pack_fields.append_all(quote! {
-                    __msgpck_n += ::msgpck::MsgPack::pack_with_writer(#field_name, __msgpck_w)?;
-                });
-            }
+        // pack all the fields
+        let (mut pack, mut write_pack) = if field_attributes.contains(&Attribute::Flatten) {
+            check_flatten_field(&field_name, &field_attributes)?;
+            let ty = &field.ty;
+            flattened_len.append_all(quote! {
+                + <#ty as ::msgpck::helpers::PackFields>::FIELD_COUNT
+            });
+            flatten_field(&field_name)
+        } else {
+            fields_len += 1;
+            pack_field(&field_name, &field_attributes)?
+        };
+        write_pack = quote! { __msgpck_n += #write_pack; };
 
-            // wrap fields pattern in brackets
-            match_fields = quote! { { #match_fields .. } };
+        // prefix the fields with their keys, if any
+        let key = keys.as_ref().and_then(|keys| keys[i]);
+        if let Some(key) = key {
+            pack = quote! { ::msgpck::helpers::pack_u64(#key).pieces().chain(#pack) };
+            write_pack = quote! {
+                __msgpck_n += ::msgpck::MsgPack::pack_with_writer(&#key, __msgpck_w)?;
+                #write_pack
+            };
         }
-        syn::Fields::Unnamed(fields) => {
-            // if there is more than one field, pack them as an array
-            let mut fields_len = 0usize;
-            for field in fields.unnamed.iter() {
-                let attributes = parse_attributes(&field.attrs, location, DeriveKind::MsgPack)?;
-                if !attributes.contains(&Attribute::Skip) {
-                    fields_len += 1;
-                }
-            }
 
-            if fields_len != 1 {
-                pack_fields.append_all(quote! {
-                    .chain(::msgpck::helpers::pack_array_header(#fields_len))
-                });
-                write_pack_fields.append_all(array_len_write(fields_len));
+        if let Some(skip_if) = parse_value::<Path>(&field_attributes, Attribute::SkipIf)? {
+            if !keyed {
+                return Err(syn::Error::new(
+                    skip_if.span(),
+                    "#[msgpck(skip_if)] is only supported for structs with #[msgpck(key)]",
+                ));
             }
 
-            unit = fields_len == 0;
-
-            for (i, field) in fields.unnamed.iter().enumerate() {
-                let field_attributes =
-                    parse_attributes(&field.attrs, location, DeriveKind::MsgPack)?;
-                if field_attributes.contains(&Attribute::Skip) {
-                    continue;
+            let skip = Ident::new(&format!("__msgpck_skip_{field_name}"), field_name.span());
+            skip_fields.append_all(quote! { let #skip: bool = #skip_if(#field_name); });
+            skipped_len.append_all(quote! { - (#skip as usize) });
+            pack = quote! {
+                (!#skip).then(|| #pack).into_iter().flatten()
+            };
+            write_pack = quote! {
+                if !#skip {
+                    #write_pack
                 }
+            };
+        }
 
-                let field_name = Ident::new(&format!("_{i}"), field.span());
-                // pattern match all the fields
-                match_fields.append_all(quote! {#field_name, });
+        packed_fields.push((key, pack, write_pack));
+    }
 
-                // pack all the fields
-                pack_fields.append_all(quote! {
-                    .chain(#field_name.pack())
-                });
+    // keyed fields are packed in the order of their keys, like the keys of a canonical map
+    packed_fields.sort_by_key(|(key, _, _)| *key);
+    let mut map_entries = quote! { ::core::iter::empty() };
+    for (key, pack, write_pack) in packed_fields {
+        match key {
+            Some(key) => map_entries.append_all(quote! {
+                .chain(#pack.map(|__msgpck_piece| (#key, __msgpck_piece)))
+            }),
+            None => pack_fields.append_all(quote! {
+                .chain(#pack)
+            }),
+        }
 
-                write_pack_fields.append_all(quote! {
-                    __msgpck_n += ::msgpck::MsgPack::pack_with_writer(#field_name, __msgpck_w)?;
-                });
-            }
+        write_pack_fields.append_all(write_pack);
+    }
 
-            // wrap fields pattern in parentheses
-            match_fields = quote! { (#match_fields ..) };
-        }
-        syn::Fields::Unit => {
-            pack_fields.append_all(quote! {
-                .chain(::msgpck::helpers::pack_array_header(0))
-            });
-            write_pack_fields.append_all(array_len_write(0));
-            unit = true;
+    // the entries of flattened keyed structs are merged with the other fields, so that all keys
+    // stay in order
+    if !flattened_entries.is_empty() {
+        for pieces in flattened_entries {
+            map_entries = quote! { ::msgpck::helpers::merge_map_entries(#map_entries, #pieces) };
         }
+        write_pack_fields = quote! {
+            __msgpck_n += ::msgpck::helpers::write_pieces(
+                #map_entries.map(|(_, __msgpck_piece)| __msgpck_piece),
+                __msgpck_w,
+            )?;
+        };
     }
 
+    let map_keys = map_keys(keys.iter().flatten().flatten(), &flattened_keys);
+
+    let flatten = !flattened_len.is_empty();
+    let unit = fields_len == 0 && !flatten;
+
+    // newtypes are packed without an array
+    let newtype = matches!(fields, Fields::Unnamed(_)) && fields_len == 1 && !flatten && !keyed;
+
+    let map_len = quote! { #fields_len #skipped_len #flattened_map_len };
+    let (pack_header, write_pack_header) = if keyed {
+        (
+            quote! {
+                .chain(::msgpck::helpers::pack_map_header(#map_len))
+            },
+            quote! {
+                __msgpck_n += ::msgpck::helpers::write_pieces(
+                    ::msgpck::helpers::pack_map_header(#map_len),
+                    __msgpck_w,
+                )?;
+            },
+        )
+    } else if newtype {
+        (quote! {}, quote! {})
+    } else if flatten {
+        // the length of the array isn't known until compile time
+        (
+            quote! {
+                .chain(::msgpck::helpers::pack_array_header(#fields_len #flattened_len))
+            },
+            quote! {
+                __msgpck_n += ::msgpck::helpers::write_pieces(
+                    ::msgpck::helpers::pack_array_header(#fields_len #flattened_len),
+                    __msgpck_w,
+                )?;
+            },
+        )
+    } else {
+        (
+            quote! {
+                .chain(::msgpck::helpers::pack_array_header(#fields_len))
+            },
+            array_len_write(fields_len),
+        )
+    };
+
+    match_fields = match fields {
+        // wrap fields pattern in brackets
+        Fields::Named(_) => quote! { { #match_fields .. } },
+        // wrap fields pattern in parentheses
+        Fields::Unnamed(_) => quote! { (#match_fields) },
+        Fields::Unit => quote! {},
+    };
+
     Ok(PackFields {
+        pack_header,
         pack_fields,
+        map_entries,
+        map_keys,
+        write_pack_header,
         write_pack_fields,
         match_fields,
+        skip_fields,
+        fields_len: quote! { #fields_len #flattened_len },
+        map_len,
         unit,
+        keyed,
+    })
+}
+
+/// Check that a `#[msgpck(flatten)]` field doesn't have any attributes that can't be flattened.
+fn check_flatten_field(field_name: &Ident, attributes: &HashSet<Attribute>) -> syn::Result<()> {
+    if let Some(attribute) = attributes.iter().find(|a| a.value().is_some()) {
+        return Err(syn::Error::new(
+            field_name.span(),
+            format!(
+                "#[msgpck(flatten)] can't be combined with #[msgpck({})]",
+                attribute.name()
+            ),
+        ));
+    }
+
+    Ok(())
+}
+
+/// Generate the expressions that pack the fields of a `#[msgpck(flatten)]` field, without an array
+/// header.
+fn flatten_field(field_name: &Ident) -> (TokenStream, TokenStream) {
+    (
+        quote! { ::msgpck::helpers::PackFields::pack_fields(#field_name) },
+        quote! { ::msgpck::helpers::PackFields::pack_fields_with_writer(#field_name, __msgpck_w)? },
+    )
+}
+
+/// Generate the tagged map entries of a `#[msgpck(flatten)]` field in a keyed struct.
+///
+/// The number of entries isn't known until the field is packed, so the entries are packed along
+/// with the `#[msgpck(skip_if)]` predicates, and their number is added to `map_len`.
+fn flatten_map_field(
+    field_name: &Ident,
+    skip_fields: &mut TokenStream,
+    map_len: &mut TokenStream,
+) -> Ident {
+    let len = Ident::new(&format!("__msgpck_len_{field_name}"), field_name.span());
+    let pieces = Ident::new(&format!("__msgpck_pieces_{field_name}"), field_name.span());
+
+    skip_fields.append_all(quote! {
+        let (#len, #pieces) = ::msgpck::helpers::PackMapFields::pack_map_fields(#field_name);
+    });
+    map_len.append_all(quote! { + #len });
+
+    pieces
+}
+
+/// Generate the expressions that pack a single field, i.e. a `pack` iterator and a
+/// `pack_with_writer` call, taking `#[msgpck(with)]`, `#[msgpck(pack_with)]` and
+/// `#[msgpck(bytes)]` into account.
+fn pack_field(
+    field_name: &Ident,
+    attributes: &HashSet<Attribute>,
+) -> syn::Result<(TokenStream, TokenStream)> {
+    let with: Option<Path> = parse_value(attributes, Attribute::With)?;
+    let pack_with: Option<Path> = parse_value(attributes, Attribute::PackWith)?;
+
+    if attributes.contains(&Attribute::Bytes) {
+        if let Some(path) = with.as_ref().or(pack_with.as_ref()) {
+            return Err(syn::Error::new(
+                path.span(),
+                "#[msgpck(bytes)] can't be combined with #[msgpck(with)] or #[msgpck(pack_with)]",
+            ));
+        }
+
+        let bytes = quote! { ::core::convert::AsRef::<[u8]>::as_ref(#field_name) };
+        return Ok((
+            quote! { ::msgpck::MsgPack::pack(#bytes) },
+            quote! { ::msgpck::MsgPack::pack_with_writer(#bytes, __msgpck_w)? },
+        ));
+    }
+
+    Ok(match (with, pack_with) {
+        (Some(with), None) => (
+            quote! { #with::pack(#field_name) },
+            quote! { #with::pack_with_writer(#field_name, __msgpck_w)? },
+        ),
+        (None, Some(pack_with)) => (
+            quote! { #pack_with(#field_name) },
+            quote! { ::msgpck::helpers::write_pieces(#pack_with(#field_name), __msgpck_w)? },
+        ),
+        (None, None) => (
+            quote! { ::msgpck::MsgPack::pack(#field_name) },
+            quote! { ::msgpck::MsgPack::pack_with_writer(#field_name, __msgpck_w)? },
+        ),
+        (Some(with), Some(_)) => {
+            return Err(syn::Error::new(
+                with.span(),
+                "#[msgpck(with)] can't be combined with #[msgpck(pack_with)]",
+            ))
+        }
     })
 }
+
+/// Generate impl MsgPack for a type marked with `#[msgpck(into = "T")]`.
+pub fn derive_pack_into(input: &DeriveInput, into: &Type) -> TokenStream {
+    let name = &input.ident;
+    let mut generics = input.generics.clone();
+    let (_, ty_generics, _) = input.generics.split_for_impl();
+    let where_clause = generics.make_where_clause();
+    where_clause.predicates.push(parse_quote! {
+        #name #ty_generics: ::core::clone::Clone + ::core::convert::Into<#into>
+    });
+    where_clause
+        .predicates
+        .push(parse_quote! { #into: ::msgpck::MsgPack });
+    let (impl_generics, _, where_clause) = generics.split_for_impl();
+
+    quote! {
+        #[automatically_derived]
+        impl #impl_generics ::msgpck::MsgPack for #name #ty_generics #where_clause {
+            fn pack(&self) -> impl Iterator<Item = ::msgpck::Piece<'_>> {
+                let value: #into = ::core::convert::Into::into(::core::clone::Clone::clone(self));
+                ::msgpck::helpers::PackOwned::new(value)
+            }
+
+            fn pack_with_writer(&self, __msgpck_w: &mut dyn ::msgpck::Write)
+                -> ::core::result::Result<usize, ::msgpck::PackErr>
+            {
+                let value: #into = ::core::convert::Into::into(::core::clone::Clone::clone(self));
+                ::msgpck::MsgPack::pack_with_writer(&value, __msgpck_w)
+            }
+        }
+    }
+}
@@ -1,13 +1,14 @@
 use proc_macro2::TokenStream;
 use quote::{quote, TokenStreamExt};
-use syn::{spanned::Spanned, DataEnum, DeriveInput};
+use syn::{DataEnum, DeriveInput, Type};
 
 use crate::{
-    attribute::{parse_attributes, AttrLocation, Attribute},
+    attribute::{parse_attributes, parse_value, AttrLocation, Attribute},
+    generics::impl_generics,
     DeriveKind, RESERVED_NAMES,
 };
 
-use super::{pack_fields, PackFields};
+use super::{derive_pack_into, pack_fields, PackFields};
 
 /// Generate impl MsgPack for an enum
 pub fn derive_pack_enum(input: &DeriveInput, data: &DataEnum) -> syn::Result<TokenStream> {
@@ -15,6 +16,10 @@ pub fn derive_pack_enum(input: &DeriveInput, data: &DataEnum) -> syn::Result<Tok
     let attributes = parse_attributes(&input.attrs, AttrLocation::Enum, DeriveKind::MsgPack)?;
     let untagged = attributes.contains(&Attribute::Untagged);
 
+    if let Some(into) = parse_value::<Type>(&attributes, Attribute::Into)? {
+        return Ok(derive_pack_into(input, &into));
+    }
+
     if RESERVED_NAMES.contains(&enum_name.to_string().as_str()) {
         return Err(syn::Error::new(
             input.ident.span(),
@@ -22,15 +27,9 @@ pub fn derive_pack_enum(input: &DeriveInput, data: &DataEnum) -> syn::Result<Tok
         ));
     }
 
-    // TODO: where-clause for enums
-    if let Some(where_clause) = &input.generics.where_clause {
-        return Err(syn::Error::new(
-            where_clause.span(),
-            "derive(MsgPack) doesn't support where-clauses for enums yet",
-        ));
-    }
-
-    let generics = &input.generics;
+    let generics = impl_generics(input, &attributes, DeriveKind::MsgPack)?;
+    let (impl_generics, _, where_clause) = generics.split_for_impl();
+    let (_, ty_generics, _) = input.generics.split_for_impl();
 
     let mut iter_enum_generics = quote! {};
     let mut iter_enum_variants = quote! {};
@@ -76,17 +75,20 @@ pub fn derive_pack_enum(input: &DeriveInput, data: &DataEnum) -> syn::Result<Tok
         // generate the actual iterator
 
         let PackFields {
+            pack_header,
             pack_fields,
+            write_pack_header,
             write_pack_fields,
             match_fields,
             unit,
+            ..
         } = pack_fields(&variant.fields, AttrLocation::EnumVariantField)?;
 
         let pack = if untagged && unit {
             // untagged variants with no fields are serialized as null
             quote! { ::core::iter::once(::msgpck::Marker::Null.into()) }
         } else if untagged {
-            quote! { ::core::iter::empty() #pack_fields }
+            quote! { ::core::iter::empty() #pack_header #pack_fields }
         } else if unit {
             quote! {
                 ::msgpck::helpers::pack_enum_header(::msgpck::EnumHeader {
@@ -100,6 +102,7 @@ pub fn derive_pack_enum(input: &DeriveInput, data: &DataEnum) -> syn::Result<Tok
                     variant: #variant_name_str.into(),
                     unit: #unit,
                 })
+                #pack_header
                 #pack_fields
             }
         };
@@ -116,7 +119,10 @@ pub fn derive_pack_enum(input: &DeriveInput, data: &DataEnum) -> syn::Result<Tok
                 __msgpck_w.write_all(&[::msgpck::Marker::Null.to_u8()])?;
             }
         } else if untagged {
-            write_pack_fields
+            quote! {
+                #write_pack_header
+                #write_pack_fields
+            }
         } else {
             writer_pack_variant_headers.append_all(quote! {
                 Self::#variant_name #match_fields =>::msgpck::EnumHeader {
@@ -128,7 +134,10 @@ pub fn derive_pack_enum(input: &DeriveInput, data: &DataEnum) -> syn::Result<Tok
             if unit {
                 quote! {}
             } else {
-                write_pack_fields
+                quote! {
+                    #write_pack_header
+                    #write_pack_fields
+                }
             }
         };
 
@@ -155,7 +164,7 @@ pub fn derive_pack_enum(input: &DeriveInput, data: &DataEnum) -> syn::Result<Tok
 
     Ok(quote! {
         #[automatically_derived]
-        impl #generics ::msgpck::MsgPack for #enum_name #generics {
+        impl #impl_generics ::msgpck::MsgPack for #enum_name #ty_generics #where_clause {
             fn pack(&self) -> impl Iterator<Item = ::msgpck::Piece<'_>> {
                 // Because we need different msgpack iterator types for each variant, we need a new
                 // enum type that impls Iterator to contain them. To avoid naming the inner iterator
@@ -1,80 +1,192 @@
-use proc_macro2::TokenStream;
-use quote::{quote, TokenStreamExt};
-use syn::{spanned::Spanned, DataStruct, DeriveInput, GenericParam};
+use std::collections::HashSet;
+
+use proc_macro2::{Ident, Span, TokenStream};
+use quote::quote;
+use syn::{spanned::Spanned, DataStruct, DeriveInput, Member, Type};
 
 use crate::{
-    attribute::{parse_attributes, AttrLocation},
+    attribute::{parse_attributes, parse_value, AttrLocation, Attribute},
+    generics::impl_generics,
     DeriveKind,
 };
 
-use super::{pack_fields, PackFields};
+use super::{derive_pack_into, pack_field, pack_fields, PackFields};
 
 /// Generate impl MsgPack for a struct
 pub fn derive_pack_struct(input: &DeriveInput, data: &DataStruct) -> syn::Result<TokenStream> {
     let struct_name = &input.ident;
-    let _attributes = parse_attributes(&input.attrs, AttrLocation::Struct, DeriveKind::MsgPack)?;
-
-    // TODO: where-clause for structs
-    if let Some(where_clause) = &input.generics.where_clause {
-        return Err(syn::Error::new(
-            where_clause.span(),
-            "derive(MsgUnpack) doesn't support where-clauses for structs",
-        ));
+    let attributes = parse_attributes(&input.attrs, AttrLocation::Struct, DeriveKind::MsgPack)?;
+
+    if let Some(into) = parse_value::<Type>(&attributes, Attribute::Into)? {
+        if attributes.contains(&Attribute::Transparent) {
+            return Err(syn::Error::new(
+                into.span(),
+                "#[msgpck(into)] can't be combined with #[msgpck(transparent)]",
+            ));
+        }
+
+        return Ok(derive_pack_into(input, &into));
     }
 
-    let mut generic_bounds = quote! {};
-    let impl_generics = &input.generics.params;
-    let mut struct_generics = quote!();
+    let generics = impl_generics(input, &attributes, DeriveKind::MsgPack)?;
+    let (impl_generics, _, where_clause) = generics.split_for_impl();
+    let (_, ty_generics, _) = input.generics.split_for_impl();
 
-    for param in impl_generics {
-        match param {
-            GenericParam::Lifetime(l) => {
-                let l = &l.lifetime;
-                struct_generics.append_all(quote! { #l, });
-            }
-            GenericParam::Type(t) => {
-                let t = &t.ident;
-                struct_generics.append_all(quote! { #t, });
-                generic_bounds.append_all(quote! {
-                    #t: ::msgpck::MsgPack,
-                });
+    if attributes.contains(&Attribute::Transparent) {
+        // pack the struct exactly like its only field
+        let (member, field_attributes) = transparent_field(data)?;
+        let field_name = Ident::new("__msgpck_field", Span::call_site());
+        let (pack, write_pack) = pack_field(&field_name, &field_attributes)?;
+
+        return Ok(quote! {
+            #[automatically_derived]
+            impl #impl_generics ::msgpck::MsgPack for #struct_name #ty_generics #where_clause {
+                fn pack<'_msgpack>(&'_msgpack self) -> impl Iterator<Item = ::msgpck::Piece<'_msgpack>> {
+                    let #field_name = &self.#member;
+                    #pack
+                }
+
+                fn pack_with_writer(&self, __msgpck_w: &mut dyn ::msgpck::Write)
+                    -> ::core::result::Result<usize, ::msgpck::PackErr>
+                {
+                    let #field_name = &self.#member;
+                    let mut __msgpck_n = 0usize;
+                    __msgpck_n += #write_pack;
+                    Ok(__msgpck_n)
+                }
             }
-            GenericParam::Const(..) => continue,
-        }
+        });
     }
 
     let PackFields {
+        pack_header,
         pack_fields,
+        map_entries,
+        map_keys,
+        write_pack_header,
         write_pack_fields,
         match_fields,
+        skip_fields,
+        fields_len,
+        map_len,
+        keyed,
         ..
     } = pack_fields(&data.fields, AttrLocation::StructField)?;
 
-    let pack_body = quote! {
-        let #struct_name #match_fields = self;
-        ::core::iter::empty() #pack_fields
-    };
+    if keyed {
+        // keyed structs are packed as maps, and can therefore only be flattened into other keyed
+        // structs
+        return Ok(quote! {
+            #[automatically_derived]
+            impl #impl_generics ::msgpck::MsgPack for #struct_name #ty_generics #where_clause {
+                fn pack<'_msgpack>(&'_msgpack self) -> impl Iterator<Item = ::msgpck::Piece<'_msgpack>> {
+                    let (__msgpck_len, __msgpck_entries) =
+                        ::msgpck::helpers::PackMapFields::pack_map_fields(self);
+                    ::msgpck::helpers::pack_map_header(__msgpck_len)
+                        .chain(__msgpck_entries.map(|(_, __msgpck_piece)| __msgpck_piece))
+                }
 
-    let writer_pack_body = quote! {
-        let #struct_name #match_fields = self;
-        let mut __msgpck_n = 0usize;
-        #write_pack_fields
-    };
+                fn pack_with_writer(&self, __msgpck_w: &mut dyn ::msgpck::Write)
+                    -> ::core::result::Result<usize, ::msgpck::PackErr>
+                {
+                    // check that the keys are unique
+                    let _ = <Self as ::msgpck::helpers::PackMapFields>::KEYS;
+
+                    let #struct_name #match_fields = self;
+                    #skip_fields
+                    let mut __msgpck_n = 0usize;
+                    #write_pack_header
+                    #write_pack_fields
+                    Ok(__msgpck_n)
+                }
+            }
+
+            #[automatically_derived]
+            impl #impl_generics ::msgpck::helpers::PackMapFields for #struct_name #ty_generics #where_clause {
+                const KEYS: ::msgpck::helpers::MapKeys = #map_keys;
+
+                fn pack_map_fields<'_msgpack>(&'_msgpack self)
+                    -> (usize, impl Iterator<Item = (u64, ::msgpck::Piece<'_msgpack>)>)
+                {
+                    // check that the keys are unique
+                    let _ = <Self as ::msgpck::helpers::PackMapFields>::KEYS;
+
+                    let #struct_name #match_fields = self;
+                    #skip_fields
+                    (#map_len, #map_entries)
+                }
+            }
+        });
+    }
 
     Ok(quote! {
         #[automatically_derived]
-        impl<#impl_generics> msgpck::MsgPack for #struct_name<#struct_generics>
-        where #generic_bounds {
+        impl #impl_generics ::msgpck::MsgPack for #struct_name #ty_generics #where_clause {
             fn pack<'_msgpack>(&'_msgpack self) -> impl Iterator<Item = ::msgpck::Piece<'_msgpack>> {
-                #pack_body
+                ::core::iter::empty()
+                    #pack_header
+                    .chain(::msgpck::helpers::PackFields::pack_fields(self))
             }
 
             fn pack_with_writer(&self, __msgpck_w: &mut dyn ::msgpck::Write)
                 -> ::core::result::Result<usize, ::msgpck::PackErr>
             {
-                #writer_pack_body
+                let mut __msgpck_n = 0usize;
+                #write_pack_header
+                __msgpck_n += ::msgpck::helpers::PackFields::pack_fields_with_writer(self, __msgpck_w)?;
+                Ok(__msgpck_n)
+            }
+        }
+
+        #[automatically_derived]
+        impl #impl_generics ::msgpck::helpers::PackFields for #struct_name #ty_generics #where_clause {
+            const FIELD_COUNT: usize = #fields_len;
+
+            fn pack_fields<'_msgpack>(&'_msgpack self) -> impl Iterator<Item = ::msgpck::Piece<'_msgpack>> {
+                let #struct_name #match_fields = self;
+                ::core::iter::empty() #pack_fields
+            }
+
+            fn pack_fields_with_writer(&self, __msgpck_w: &mut dyn ::msgpck::Write)
+                -> ::core::result::Result<usize, ::msgpck::PackErr>
+            {
+                let #struct_name #match_fields = self;
+                let mut __msgpck_n = 0usize;
+                #write_pack_fields
                 Ok(__msgpck_n)
             }
         }
     })
 }
+
+/// Find the only (non-skipped) field of a struct marked with `#[msgpck(transparent)]`.
+fn transparent_field(data: &DataStruct) -> syn::Result<(Member, HashSet<Attribute>)> {
+    let mut transparent_field = None;
+    for (i, field) in data.fields.iter().enumerate() {
+        let field_attributes =
+            parse_attributes(&field.attrs, AttrLocation::StructField, DeriveKind::MsgPack)?;
+        if field_attributes.contains(&Attribute::Skip) {
+            continue;
+        }
+
+        if transparent_field.is_some() {
+            return Err(syn::Error::new(
+                field.span(),
+                "#[msgpck(transparent)] requires a struct with exactly one field",
+            ));
+        }
+
+        let member = match &field.ident {
+            Some(ident) => Member::Named(ident.clone()),
+            None => Member::Unnamed(i.into()),
+        };
+        transparent_field = Some((member, field_attributes));
+    }
+
+    transparent_field.ok_or_else(|| {
+        syn::Error::new(
+            data.fields.span(),
+            "#[msgpck(transparent)] requires a struct with exactly one field",
+        )
+    })
+}
@@ -1,2 +1,207 @@
+use std::collections::HashSet;
+
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{parse_quote, spanned::Spanned, DeriveInput, Field, Path, Type};
+
+use crate::{
+    attribute::{parse_value, Attribute},
+    generics::add_msgpck_lifetime,
+};
+
 pub mod enums;
 pub mod structs;
+
+/// Generate the expression that unpacks a single field from `bytes` using `ctx`, taking
+/// `#[msgpck(with)]`, `#[msgpck(unpack_with)]` and `#[msgpck(bytes)]` into account.
+///
+/// `segments` are added to the path of any error, innermost first, see [field_segment].
+pub fn unpack_field(
+    attributes: &HashSet<Attribute>,
+    segments: &[TokenStream],
+) -> syn::Result<TokenStream> {
+    let with: Option<Path> = parse_value(attributes, Attribute::With)?;
+    let unpack_with: Option<Path> = parse_value(attributes, Attribute::UnpackWith)?;
+
+    let unpack = match (with, unpack_with) {
+        (Some(path), _) | (_, Some(path)) if attributes.contains(&Attribute::Bytes) => {
+            return Err(syn::Error::new(
+                path.span(),
+                "#[msgpck(bytes)] can't be combined with #[msgpck(with)] or #[msgpck(unpack_with)]",
+            ))
+        }
+        (None, None) if attributes.contains(&Attribute::Bytes) => {
+            quote! { ::msgpck::helpers::unpack_bytes(bytes, ctx) }
+        }
+        (Some(with), None) => quote! { #with::unpack(bytes) },
+        (None, Some(unpack_with)) => quote! { #unpack_with(bytes) },
+        (None, None) => quote! { ::msgpck::MsgUnpack::unpack_with_ctx(bytes, ctx) },
+        (Some(with), Some(_)) => {
+            return Err(syn::Error::new(
+                with.span(),
+                "#[msgpck(with)] can't be combined with #[msgpck(unpack_with)]",
+            ))
+        }
+    };
+
+    let value = quote! { __msgpck_value };
+    let validate = validate(attributes, &value)?;
+    let unpack = if validate.is_empty() {
+        unpack
+    } else {
+        quote! {
+            #unpack.and_then(|#value| {
+                #validate
+                Ok(#value)
+            })
+        }
+    };
+
+    if segments.is_empty() {
+        return Ok(quote! { #unpack? });
+    }
+
+    Ok(quote! {{
+        let __msgpck_start = *bytes;
+        #unpack.map_err(|e| {
+            #(let e = ctx.error_at(#segments, __msgpck_start, e);)*
+            e
+        })?
+    }})
+}
+
+/// Generate `MsgUnpack::unpack`, which calls `MsgUnpack::unpack_with_ctx` with a new context.
+pub fn unpack_with_new_ctx() -> TokenStream {
+    quote! {
+        fn unpack(bytes: &mut &'_msgpck [u8]) -> Result<Self, ::msgpck::UnpackErr>
+        where
+            Self: Sized,
+        {
+            Self::unpack_with_ctx(bytes, &mut ::msgpck::UnpackCtx::new())
+        }
+    }
+}
+
+/// Generate the [PathSegment](msgpck::PathSegment) of a field, i.e. its name or its index.
+pub fn field_segment(field: &Field, index: usize) -> TokenStream {
+    match &field.ident {
+        Some(ident) => {
+            let name = ident.to_string();
+            quote! { ::msgpck::PathSegment::Field(#name) }
+        }
+        None => quote! { ::msgpck::PathSegment::Index(#index) },
+    }
+}
+
+/// Generate the statement that checks `value` using `#[msgpck(validate = "path")]`, if present.
+pub fn validate(attributes: &HashSet<Attribute>, value: &TokenStream) -> syn::Result<TokenStream> {
+    let Some(validate) = parse_value::<Path>(attributes, Attribute::Validate)? else {
+        return Ok(quote! {});
+    };
+
+    Ok(quote! {
+        #validate(&#value).map_err(|message| ::msgpck::UnpackErr::Invalid { message })?;
+    })
+}
+
+/// How to convert the value unpacked by [derive_unpack_from].
+pub enum Conversion {
+    /// `#[msgpck(from = "T")]`
+    From,
+
+    /// `#[msgpck(try_from = "T")]`
+    TryFrom,
+}
+
+/// Generate impl MsgUnpack for a type marked with `#[msgpck(from = "T")]` or
+/// `#[msgpck(try_from = "T")]`.
+pub fn derive_unpack_from(
+    input: &DeriveInput,
+    from: &Type,
+    conversion: Conversion,
+    validate: TokenStream,
+) -> TokenStream {
+    let name = &input.ident;
+    let mut generics = input.generics.clone();
+    let (_, ty_generics, _) = input.generics.split_for_impl();
+
+    add_msgpck_lifetime(&mut generics);
+
+    let where_clause = generics.make_where_clause();
+    where_clause
+        .predicates
+        .push(parse_quote! { #from: ::msgpck::MsgUnpack<'_msgpck> });
+
+    let convert = match conversion {
+        Conversion::From => {
+            where_clause.predicates.push(parse_quote! {
+                #name #ty_generics: ::core::convert::From<#from>
+            });
+            quote! { ::core::convert::From::from(value) }
+        }
+        Conversion::TryFrom => {
+            where_clause.predicates.push(parse_quote! {
+                #name #ty_generics: ::core::convert::TryFrom<#from>
+            });
+            where_clause.predicates.push(parse_quote! {
+                <#name #ty_generics as ::core::convert::TryFrom<#from>>::Error: ::core::fmt::Display
+            });
+            quote! {
+                ::core::convert::TryFrom::try_from(value).map_err(|e| {
+                    ::msgpck::UnpackErr::Conversion(::msgpck::ConversionErr::new(e))
+                })?
+            }
+        }
+    };
+
+    let (impl_generics, _, where_clause) = generics.split_for_impl();
+    let unpack = unpack_with_new_ctx();
+
+    quote! {
+        #[automatically_derived]
+        impl #impl_generics ::msgpck::MsgUnpack<'_msgpck> for #name #ty_generics #where_clause {
+            #unpack
+
+            fn unpack_with_ctx(
+                bytes: &mut &'_msgpck [u8],
+                ctx: &mut ::msgpck::UnpackCtx,
+            ) -> Result<Self, ::msgpck::UnpackErr>
+            where
+                Self: Sized,
+            {
+                let value: #from = ::msgpck::MsgUnpack::unpack_with_ctx(bytes, ctx)?;
+                let value: Self = #convert;
+                #validate
+                Ok(value)
+            }
+        }
+    }
+}
+
+/// Check the container attributes for `#[msgpck(from)]` and `#[msgpck(try_from)]`, and generate
+/// impl MsgUnpack using them if present.
+pub fn derive_unpack_conversion(
+    input: &DeriveInput,
+    attributes: &HashSet<Attribute>,
+) -> syn::Result<Option<TokenStream>> {
+    let from: Option<Type> = parse_value(attributes, Attribute::From)?;
+    let try_from: Option<Type> = parse_value(attributes, Attribute::TryFrom)?;
+    let validate = validate(attributes, &quote! { value })?;
+
+    Ok(match (from, try_from) {
+        (Some(from), None) => Some(derive_unpack_from(input, &from, Conversion::From, validate)),
+        (None, Some(try_from)) => Some(derive_unpack_from(
+            input,
+            &try_from,
+            Conversion::TryFrom,
+            validate,
+        )),
+        (None, None) => None,
+        (Some(from), Some(_)) => {
+            return Err(syn::Error::new(
+                from.span(),
+                "#[msgpck(from)] can't be combined with #[msgpck(try_from)]",
+            ))
+        }
+    })
+}
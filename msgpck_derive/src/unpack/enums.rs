@@ -4,32 +4,25 @@ use syn::{spanned::Spanned, DataEnum, DeriveInput, Expr, ExprUnary, Fields, Lit,
 
 use crate::{
     attribute::{parse_attributes, AttrLocation, Attribute},
+    generics::impl_generics,
     DeriveKind,
 };
 
+use super::{derive_unpack_conversion, field_segment, unpack_field, unpack_with_new_ctx, validate};
+
 /// Generate impl MsgUnpack for an enum
 pub fn derive_unpack_enum(input: &DeriveInput, data: &DataEnum) -> syn::Result<TokenStream> {
     let enum_name = &input.ident;
-    let _attributes = parse_attributes(&input.attrs, AttrLocation::Enum, DeriveKind::MsgUnpack)?;
-
-    let generics = &input.generics;
-    let generics: TokenStream = generics
-        .type_params()
-        .map(|t| quote! {#t,})
-        .chain(generics.const_params().map(|c| quote! {#c,}))
-        .collect();
-    let lifetimes: TokenStream = input
-        .generics
-        .lifetimes()
-        .map(|_l| quote! { '_msgpck, })
-        .collect();
-    let mut impl_generics = quote! { '_msgpck, #generics };
-
-    for l in input.generics.lifetimes() {
-        impl_generics.append_all(quote! { #l, 'msgpck: #l, });
+    let attributes = parse_attributes(&input.attrs, AttrLocation::Enum, DeriveKind::MsgUnpack)?;
+
+    if let Some(conversion) = derive_unpack_conversion(input, &attributes)? {
+        return Ok(conversion);
     }
 
-    let ty_generics = quote! { <#lifetimes #generics> };
+    let validate = validate(&attributes, &quote! { value })?;
+    let generics = impl_generics(input, &attributes, DeriveKind::MsgUnpack)?;
+    let (impl_generics, _, where_clause) = generics.split_for_impl();
+    let (_, ty_generics, _) = input.generics.split_for_impl();
 
     let mut unpack_variants = quote! {};
     let mut other_variant = None;
@@ -122,7 +115,7 @@ pub fn derive_unpack_enum(input: &DeriveInput, data: &DataEnum) -> syn::Result<T
                     return Err(UnpackErr::ExpectedUnitVariant);
                 }
 
-                let array_len = unpack_array_header(bytes)?;
+                let array_len = unpack_array_header_with_ctx(bytes, ctx)?;
 
                 if array_len < #n {
                     return Err(UnpackErr::MissingFields {
@@ -138,11 +131,12 @@ pub fn derive_unpack_enum(input: &DeriveInput, data: &DataEnum) -> syn::Result<T
         };
 
         let mut construct_fields = quote! {};
+        let variant_segment = quote! { ::msgpck::PathSegment::Variant(#variant_name_str) };
 
         match &variant.fields {
             syn::Fields::Unit => {}
             syn::Fields::Named(fields) => {
-                for field in &fields.named {
+                for (i, field) in fields.named.iter().enumerate() {
                     let field_attributes = parse_attributes(
                         &field.attrs,
                         AttrLocation::EnumVariantField,
@@ -161,12 +155,14 @@ pub fn derive_unpack_enum(input: &DeriveInput, data: &DataEnum) -> syn::Result<T
                     construct_fields.append_all(if field_attributes.contains(&Attribute::Skip) {
                         quote! { #field_name: ::core::default::Default::default(), }
                     } else {
-                        quote! { #field_name: MsgUnpack::unpack(bytes)?, }
+                        let segments = [field_segment(field, i), variant_segment.clone()];
+                        let unpack = unpack_field(&field_attributes, &segments)?;
+                        quote! { #field_name: #unpack, }
                     })
                 }
             }
             syn::Fields::Unnamed(fields) => {
-                for field in &fields.unnamed {
+                for (i, field) in fields.unnamed.iter().enumerate() {
                     let field_attributes = parse_attributes(
                         &field.attrs,
                         AttrLocation::EnumVariantField,
@@ -187,7 +183,9 @@ pub fn derive_unpack_enum(input: &DeriveInput, data: &DataEnum) -> syn::Result<T
                     construct_fields.append_all(if field_attributes.contains(&Attribute::Skip) {
                         quote! { ::core::default::Default::default(), }
                     } else {
-                        quote! { MsgUnpack::unpack(bytes)?, }
+                        let segments = [field_segment(field, i), variant_segment.clone()];
+                        let unpack = unpack_field(&field_attributes, &segments)?;
+                        quote! { #unpack, }
                     })
                 }
             }
@@ -214,21 +212,33 @@ pub fn derive_unpack_enum(input: &DeriveInput, data: &DataEnum) -> syn::Result<T
         None => quote! { _unknown_variant => return Err(UnpackErr::UnknownVariant) },
     };
 
+    let type_name = enum_name.to_string();
+    let unpack = unpack_with_new_ctx();
+
     Ok(quote! {
         #[automatically_derived]
-        impl<#impl_generics> ::msgpck::MsgUnpack<'_msgpck> for #enum_name #ty_generics {
-            fn unpack(bytes: &mut &'_msgpck [u8]) -> Result<Self, ::msgpck::UnpackErr>
+        impl #impl_generics ::msgpck::MsgUnpack<'_msgpck> for #enum_name #ty_generics #where_clause {
+            #unpack
+
+            fn unpack_with_ctx(
+                bytes: &mut &'_msgpck [u8],
+                ctx: &mut ::msgpck::UnpackCtx,
+            ) -> Result<Self, ::msgpck::UnpackErr>
             where
-                Self: Sized + '_msgpck,
+                Self: Sized,
             {
-                use ::msgpck::{UnpackErr, Variant::*, MsgUnpack};
-                use ::msgpck::helpers::{unpack_enum_header, unpack_array_header};
-
-                let header = unpack_enum_header(bytes)?;
-
-                Ok(match &header.variant {
-                    #unpack_variants
-                    #unknown_variant_match
+                use ::msgpck::{UnpackErr, Variant::*};
+                use ::msgpck::helpers::{unpack_enum_header_with_ctx, unpack_array_header_with_ctx};
+
+                ::msgpck::helpers::unpack_with_type(#type_name, bytes, ctx, |bytes, ctx| {
+                    let header = unpack_enum_header_with_ctx(bytes, ctx)?;
+
+                    let value = match &header.variant {
+                        #unpack_variants
+                        #unknown_variant_match
+                    };
+                    #validate
+                    Ok(value)
                 })
             }
         }
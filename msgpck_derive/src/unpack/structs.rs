@@ -1,69 +1,51 @@
 use proc_macro2::TokenStream;
 use quote::{quote, TokenStreamExt};
-use syn::{spanned::Spanned, DataStruct, DeriveInput, Fields, GenericParam};
+use syn::{spanned::Spanned, DataStruct, DeriveInput, Fields, Index};
 
 use crate::{
-    attribute::{parse_attributes, AttrLocation, Attribute},
-    DeriveKind,
+    attribute::{parse_attributes, parse_field_keys, AttrLocation, Attribute},
+    generics::impl_generics,
+    map_keys, DeriveKind,
 };
 
+use super::{derive_unpack_conversion, field_segment, unpack_field, unpack_with_new_ctx, validate};
+
 /// Generate impl MsgUnpack for a struct
 pub fn derive_unpack_struct(input: &DeriveInput, data: &DataStruct) -> syn::Result<TokenStream> {
     let struct_name = &input.ident;
-    let _attributes = parse_attributes(&input.attrs, AttrLocation::Struct, DeriveKind::MsgUnpack)?;
-
-    let mut struct_len = 0usize;
-    for field in data.fields.iter() {
-        let field_attributes = parse_attributes(
-            &field.attrs,
-            AttrLocation::StructField,
-            DeriveKind::MsgUnpack,
-        )?;
+    let attributes = parse_attributes(&input.attrs, AttrLocation::Struct, DeriveKind::MsgUnpack)?;
+    let transparent = attributes.contains(&Attribute::Transparent);
 
-        if !field_attributes.contains(&Attribute::Skip) {
-            struct_len += 1;
+    if let Some(conversion) = derive_unpack_conversion(input, &attributes)? {
+        if transparent {
+            return Err(syn::Error::new(
+                input.ident.span(),
+                "#[msgpck(from)] and #[msgpck(try_from)] can't be combined with #[msgpck(transparent)]",
+            ));
         }
-    }
 
-    // TODO: where-clause for structs
-    if let Some(where_clause) = &input.generics.where_clause {
-        return Err(syn::Error::new(
-            where_clause.span(),
-            "derive(MsgUnpack) doesn't support where-clauses for structs",
-        ));
+        return Ok(conversion);
     }
 
+    let generics = impl_generics(input, &attributes, DeriveKind::MsgUnpack)?;
+    let (impl_generics, _, where_clause) = generics.split_for_impl();
+    let (_, ty_generics, _) = input.generics.split_for_impl();
+
     let mut unpack_fields = quote! {};
-    let mut generic_bounds = quote! {};
-    let impl_generics = &input.generics.params;
-    let mut struct_generics = quote! {};
-
-    for param in impl_generics {
-        match param {
-            GenericParam::Lifetime(l) => {
-                let l = &l.lifetime;
-                struct_generics.append_all(quote! { #l, });
-                generic_bounds.append_all(quote! {
-                    '_msgpack: #l,
-                });
-            }
-            GenericParam::Type(t) => {
-                let t = &t.ident;
-                struct_generics.append_all(quote! { #t, });
-                generic_bounds.append_all(quote! {
-                    #t: ::msgpck::MsgUnpack<'_msgpack>,
-                });
-            }
-            GenericParam::Const(..) => continue,
-        }
-    }
 
-    for field in data.fields.iter() {
+    // the number of fields that aren't skipped or flattened
+    let mut struct_len = 0usize;
+
+    // the number of fields in flattened structs, which isn't known until compile time
+    let mut flattened_len = quote! {};
+
+    for (i, field) in data.fields.iter().enumerate() {
         let field_attributes = parse_attributes(
             &field.attrs,
             AttrLocation::StructField,
             DeriveKind::MsgUnpack,
         )?;
+        let segment = field_segment(field, i);
 
         if field_attributes.contains(&Attribute::Default) {
             return Err(syn::Error::new(
@@ -72,17 +54,40 @@ pub fn derive_unpack_struct(input: &DeriveInput, data: &DataStruct) -> syn::Resu
             ));
         }
 
-        if field_attributes.contains(&Attribute::Skip) {
-            unpack_fields.append_all(match &field.ident {
-                Some(ident) => quote! { #ident: ::core::default::Default::default(), },
-                None => quote! { ::core::default::Default::default(), },
+        let unpack = if field_attributes.contains(&Attribute::Skip) {
+            quote! { ::core::default::Default::default() }
+        } else if field_attributes.contains(&Attribute::Flatten) {
+            if let Some(attribute) = field_attributes.iter().find(|a| a.value().is_some()) {
+                return Err(syn::Error::new(
+                    field.span(),
+                    format!(
+                        "#[msgpck(flatten)] can't be combined with #[msgpck({})]",
+                        attribute.name()
+                    ),
+                ));
+            }
+
+            let ty = &field.ty;
+            flattened_len.append_all(quote! {
+                + <#ty as ::msgpck::helpers::UnpackFields<'_msgpck>>::FIELD_COUNT
             });
+            quote! {{
+                let __msgpck_start = *bytes;
+                <#ty as ::msgpck::helpers::UnpackFields<'_msgpck>>::unpack_fields(bytes, ctx)
+                    .map_err(|e| ctx.error_at(#segment, __msgpck_start, e))?
+            }}
         } else {
-            unpack_fields.append_all(match &field.ident {
-                Some(ident) => quote! { #ident: MsgUnpack::unpack(bytes)?, },
-                None => quote! { MsgUnpack::unpack(bytes)?, },
-            });
-        }
+            struct_len += 1;
+
+            // transparent structs are unpacked exactly like their field, without any context
+            let segments = if transparent { vec![] } else { vec![segment] };
+            unpack_field(&field_attributes, &segments)?
+        };
+
+        unpack_fields.append_all(match &field.ident {
+            Some(ident) => quote! { #ident: #unpack, },
+            None => quote! { #unpack, },
+        });
     }
 
     // wrap the fields in the appropriate brackets, if any
@@ -92,46 +97,289 @@ pub fn derive_unpack_struct(input: &DeriveInput, data: &DataStruct) -> syn::Resu
         Fields::Unit => quote! {},
     };
 
-    // newtype structs are serialized without using an array, this is to maintain compatibility with serde
-    let unpack_body = if matches!(&data.fields, Fields::Unnamed(..)) && struct_len == 1 {
-        quote! {
-            let value = Self #unpack_fields;
-            Ok(value)
+    let flatten = !flattened_len.is_empty();
+    let validate = validate(&attributes, &quote! { value })?;
+    let unpack = unpack_with_new_ctx();
+
+    if let Some(keys) = parse_field_keys(&data.fields, DeriveKind::MsgUnpack)? {
+        if transparent {
+            return Err(syn::Error::new(
+                input.ident.span(),
+                "#[msgpck(key)] can't be combined with #[msgpck(transparent)]",
+            ));
+        }
+
+        return derive_unpack_keyed(input, data, &keys, validate, unpack);
+    }
+
+    if transparent {
+        if struct_len != 1 || flatten {
+            return Err(syn::Error::new(
+                data.fields.span(),
+                "#[msgpck(transparent)] requires a struct with exactly one field",
+            ));
         }
+
+        return Ok(quote! {
+            #[automatically_derived]
+            impl #impl_generics ::msgpck::MsgUnpack<'_msgpck> for #struct_name #ty_generics #where_clause {
+                #unpack
+
+                fn unpack_with_ctx(
+                    bytes: &mut &'_msgpck [u8],
+                    ctx: &mut ::msgpck::UnpackCtx,
+                ) -> Result<Self, ::msgpck::UnpackErr>
+                where
+                    Self: Sized,
+                {
+                    let value = Self #unpack_fields;
+                    #validate
+                    Ok(value)
+                }
+            }
+        });
+    }
+
+    // newtype structs are serialized without using an array, this is to maintain compatibility with serde
+    let newtype = matches!(&data.fields, Fields::Unnamed(..)) && struct_len == 1 && !flatten;
+    let unpack_header = if newtype {
+        quote! {}
     } else {
         quote! {
-            let n = unpack_array_header(bytes)?;
+            let n = unpack_array_header_with_ctx(bytes, ctx)?;
+            let expected = #struct_len #flattened_len;
 
-            if n < #struct_len {
-                return Err(UnpackErr::MissingFields {
-                    got: n,
-                    expected: #struct_len
-                });
+            if n < expected {
+                return Err(UnpackErr::MissingFields { got: n, expected });
             }
-            if n > #struct_len {
-                return Err(UnpackErr::TooManyFields {
-                    got: n,
-                    expected: #struct_len
-                });
+            if n > expected {
+                return Err(UnpackErr::TooManyFields { got: n, expected });
             }
+        }
+    };
+
+    let type_name = struct_name.to_string();
+
+    Ok(quote! {
+        #[automatically_derived]
+        impl #impl_generics ::msgpck::MsgUnpack<'_msgpck> for #struct_name #ty_generics #where_clause {
+            #unpack
+
+            fn unpack_with_ctx(
+                bytes: &mut &'_msgpck [u8],
+                ctx: &mut ::msgpck::UnpackCtx,
+            ) -> Result<Self, ::msgpck::UnpackErr>
+            where
+                Self: Sized,
+            {
+                use ::msgpck::{UnpackErr, helpers::unpack_array_header_with_ctx};
 
-            let value = Self #unpack_fields;
+                ::msgpck::helpers::unpack_with_type(#type_name, bytes, ctx, |bytes, ctx| {
+                    #unpack_header
+
+                    ::msgpck::helpers::UnpackFields::unpack_fields(bytes, ctx)
+                })
+            }
+        }
+
+        #[automatically_derived]
+        impl #impl_generics ::msgpck::helpers::UnpackFields<'_msgpck> for #struct_name #ty_generics #where_clause {
+            const FIELD_COUNT: usize = #struct_len #flattened_len;
 
-            Ok(value)
+            fn unpack_fields(
+                bytes: &mut &'_msgpck [u8],
+                ctx: &mut ::msgpck::UnpackCtx,
+            ) -> Result<Self, ::msgpck::UnpackErr>
+            where
+                Self: Sized,
+            {
+                let value = Self #unpack_fields;
+                #validate
+                Ok(value)
+            }
         }
+    })
+}
+
+/// Generate impl MsgUnpack for a struct where the fields are marked with `#[msgpck(key = N)]`.
+///
+/// The struct is unpacked from a map, where the keys may appear in any order. Unknown keys are
+/// skipped, and missing fields are set to `Default::default()`.
+fn derive_unpack_keyed(
+    input: &DeriveInput,
+    data: &DataStruct,
+    keys: &[Option<u64>],
+    validate: TokenStream,
+    unpack: TokenStream,
+) -> syn::Result<TokenStream> {
+    let struct_name = &input.ident;
+    let attributes = parse_attributes(&input.attrs, AttrLocation::Struct, DeriveKind::MsgUnpack)?;
+    let generics = impl_generics(input, &attributes, DeriveKind::MsgUnpack)?;
+    let (impl_generics, _, where_clause) = generics.split_for_impl();
+    let (_, ty_generics, _) = input.generics.split_for_impl();
+
+    // the types and initial values of the unpacked fields, in a tuple
+    let mut map_fields_ty = quote! {};
+    let mut empty_map_fields = quote! {};
+
+    let mut match_keys = quote! {};
+    let mut unpack_flattened = quote! {};
+    let mut unpack_fields = quote! {};
+
+    // the keys of flattened fields
+    let mut flattened_keys = vec![];
+
+    for ((i, field), key) in data.fields.iter().enumerate().zip(keys) {
+        let field_attributes = parse_attributes(
+            &field.attrs,
+            AttrLocation::StructField,
+            DeriveKind::MsgUnpack,
+        )?;
+        let ty = &field.ty;
+        let index = Index::from(i);
+        let segment = field_segment(field, i);
+
+        // fields that weren't in the map are set to their default value
+        let value = if let Some(key) = key {
+            let unpack = unpack_field(&field_attributes, &[segment])?;
+
+            map_fields_ty.append_all(quote! { ::core::option::Option<#ty>, });
+            empty_map_fields.append_all(quote! { ::core::option::Option::None, });
+            match_keys.append_all(quote! {
+                #key => {
+                    fields.#index = ::core::option::Option::Some(#unpack);
+                    return Ok(true);
+                }
+            });
+
+            quote! { fields.#index.unwrap_or_default() }
+        } else if field_attributes.contains(&Attribute::Flatten) {
+            let flattened = quote! { <#ty as ::msgpck::helpers::UnpackMapFields<'_msgpck>> };
+
+            flattened_keys.push(quote! { #flattened::KEYS });
+            map_fields_ty.append_all(quote! { #flattened::MapFields, });
+            empty_map_fields.append_all(quote! { #flattened::empty_map_fields(), });
+            unpack_flattened.append_all(quote! {
+                let __msgpck_start = *bytes;
+                if #flattened::unpack_map_field(&mut fields.#index, key, bytes, ctx)
+                    .map_err(|e| ctx.error_at(#segment, __msgpck_start, e))?
+                {
+                    return Ok(true);
+                }
+            });
+
+            quote! {
+                #flattened::from_map_fields(fields.#index, map, ctx)
+                    .map_err(|e| ctx.error_at(#segment, map, e))?
+            }
+        } else {
+            // skipped fields aren't unpacked, but keep their place in the tuple
+            map_fields_ty.append_all(quote! { (), });
+            empty_map_fields.append_all(quote! { (), });
+
+            quote! { ::core::default::Default::default() }
+        };
+
+        unpack_fields.append_all(match &field.ident {
+            Some(ident) => quote! { #ident: #value, },
+            None => quote! { #value, },
+        });
+    }
+
+    // wrap the fields in the appropriate brackets, if any
+    unpack_fields = match &data.fields {
+        Fields::Named(_) => quote! { {#unpack_fields} },
+        Fields::Unnamed(_) => quote! { (#unpack_fields) },
+        Fields::Unit => quote! {},
     };
 
+    // keys that don't belong to any field of this struct may belong to a flattened one
+    if !match_keys.is_empty() {
+        match_keys = quote! {
+            match key {
+                #match_keys
+                _ => {}
+            }
+        };
+    }
+
+    // the map and the context are only used for the errors of flattened fields
+    let (map, ctx) = match unpack_flattened.is_empty() {
+        true => (quote! { _map }, quote! { _ctx }),
+        false => (quote! { map }, quote! { ctx }),
+    };
+
+    let map_keys = map_keys(keys.iter().flatten(), &flattened_keys);
+    let type_name = struct_name.to_string();
+
     Ok(quote! {
         #[automatically_derived]
-        impl<'_msgpack, #impl_generics> ::msgpck::MsgUnpack<'_msgpack> for #struct_name<#struct_generics>
-        where #generic_bounds {
-            fn unpack(bytes: &mut &'_msgpack [u8]) -> Result<Self, ::msgpck::UnpackErr>
+        impl #impl_generics ::msgpck::MsgUnpack<'_msgpck> for #struct_name #ty_generics #where_clause {
+            #unpack
+
+            fn unpack_with_ctx(
+                bytes: &mut &'_msgpck [u8],
+                ctx: &mut ::msgpck::UnpackCtx,
+            ) -> Result<Self, ::msgpck::UnpackErr>
             where
                 Self: Sized,
             {
-                use ::msgpck::{MsgUnpack, UnpackErr, helpers::unpack_array_header};
+                use ::msgpck::helpers::UnpackMapFields;
+
+                ::msgpck::helpers::unpack_with_type(#type_name, bytes, ctx, |bytes, ctx| {
+                    let map = *bytes;
+                    let mut fields = <Self as UnpackMapFields<'_msgpck>>::empty_map_fields();
+
+                    // a canonical map has sorted keys without duplicates, which also covers unknown keys
+                    let mut prev_key = ::core::option::Option::None;
+                    let n = ::msgpck::helpers::unpack_map_header_with_ctx(bytes, ctx)?;
+                    for _ in 0..n {
+                        let key = ::msgpck::helpers::unpack_map_key::<u64>(bytes, ctx, &mut prev_key)?;
+                        if !<Self as UnpackMapFields<'_msgpck>>::unpack_map_field(&mut fields, key, bytes, ctx)? {
+                            ::msgpck::helpers::skip_value(bytes)?;
+                        }
+                    }
 
-                #unpack_body
+                    <Self as UnpackMapFields<'_msgpck>>::from_map_fields(fields, map, ctx)
+                })
+            }
+        }
+
+        #[automatically_derived]
+        impl #impl_generics ::msgpck::helpers::UnpackMapFields<'_msgpck> for #struct_name #ty_generics #where_clause {
+            const KEYS: ::msgpck::helpers::MapKeys = #map_keys;
+
+            type MapFields = (#map_fields_ty);
+
+            fn empty_map_fields() -> Self::MapFields {
+                (#empty_map_fields)
+            }
+
+            fn unpack_map_field(
+                fields: &mut Self::MapFields,
+                key: u64,
+                bytes: &mut &'_msgpck [u8],
+                ctx: &mut ::msgpck::UnpackCtx,
+            ) -> Result<bool, ::msgpck::UnpackErr> {
+                // check that the keys are unique
+                let _ = <Self as ::msgpck::helpers::UnpackMapFields<'_msgpck>>::KEYS;
+
+                #match_keys
+                #unpack_flattened
+                Ok(false)
+            }
+
+            fn from_map_fields(
+                fields: Self::MapFields,
+                #map: &'_msgpck [u8],
+                #ctx: &mut ::msgpck::UnpackCtx,
+            ) -> Result<Self, ::msgpck::UnpackErr>
+            where
+                Self: Sized,
+            {
+                let value = Self #unpack_fields;
+                #validate
+                Ok(value)
             }
         }
     })
@@ -0,0 +1,115 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use msgpck::{
+    pack_slice, pack_vec, unpack_slice, unpack_slice_with_context, Bytes, MsgPack, MsgUnpack, Seq,
+    UnpackErr,
+};
+
+#[derive(Debug, PartialEq, MsgPack, MsgUnpack)]
+pub struct Signed {
+    #[msgpck(unpack_with = "msgpck::helpers::unpack_byte_array")]
+    pub hash: [u8; 4],
+    pub payload: [u16; 2],
+}
+
+#[test]
+fn array_without_alloc() {
+    let mut buf = [0u8; 16];
+    let n = pack_slice(&mut buf, &[1u16, 0x1234, 3]).unwrap();
+    assert_eq!(&buf[..n], [0x93, 0x01, 0xcd, 0x12, 0x34, 0x03]);
+
+    let unpacked: [u16; 3] = unpack_slice(&buf[..n]).unwrap();
+    assert_eq!(unpacked, [1, 0x1234, 3]);
+}
+
+static DROPPED: AtomicUsize = AtomicUsize::new(0);
+
+/// A value which counts how often it's dropped.
+#[derive(Debug, MsgUnpack)]
+pub struct Counted(pub u8);
+
+impl Drop for Counted {
+    fn drop(&mut self) {
+        DROPPED.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+#[test]
+fn array_drops_unpacked_elements_on_error() {
+    let packed = pack_vec(&(1u8, 2u8, "three")).unwrap();
+    assert!(unpack_slice::<[Counted; 3]>(&packed).is_err());
+    assert_eq!(DROPPED.load(Ordering::SeqCst), 2);
+}
+
+#[test]
+fn slice_as_array() {
+    let values: &[u16] = &[1, 0x1234, 3];
+    let packed = pack_vec(&Seq(values)).unwrap();
+    assert_eq!(packed, pack_vec(&[1u16, 0x1234, 3]).unwrap());
+    assert_eq!(packed, pack_vec(&values.to_vec()).unwrap());
+
+    let mut buf = [0u8; 16];
+    let n = pack_slice(&mut buf, &Seq(values)).unwrap();
+    assert_eq!(&buf[..n], packed);
+
+    // unlike a byte slice, which is packed as bin
+    let bytes: &[u8] = &[1, 2];
+    assert_eq!(pack_vec(&Seq(bytes)).unwrap(), [0x92, 0x01, 0x02]);
+    assert_eq!(pack_vec(&Bytes(bytes)).unwrap(), [0xc4, 0x02, 0x01, 0x02]);
+}
+
+#[test]
+fn array_wrong_length() {
+    let packed = pack_vec(&[1u8, 2, 3]).unwrap();
+
+    let err = unpack_slice::<[u8; 4]>(&packed).unwrap_err();
+    assert!(matches!(
+        err,
+        UnpackErr::MissingFields {
+            got: 3,
+            expected: 4
+        }
+    ));
+
+    let err = unpack_slice::<[u8; 2]>(&packed).unwrap_err();
+    assert!(matches!(
+        err,
+        UnpackErr::TooManyFields {
+            got: 3,
+            expected: 2
+        }
+    ));
+}
+
+#[test]
+fn array_error_path() {
+    let packed = pack_vec(&(1u8, 2u8, "three")).unwrap();
+
+    let err = unpack_slice_with_context::<[u8; 3]>(&packed).unwrap_err();
+    assert_eq!(err.path(), [msgpck::PathSegment::Index(2)]);
+}
+
+#[test]
+fn byte_array_from_bin() {
+    let signed = Signed {
+        hash: [0xde, 0xad, 0xbe, 0xef],
+        payload: [1, 2],
+    };
+
+    // the hash is packed as an array, but may also be unpacked from a bin
+    let packed = pack_vec(&signed).unwrap();
+    assert_eq!(unpack_slice::<Signed>(&packed).unwrap(), signed);
+
+    let packed = [0x92, 0xc4, 0x04, 0xde, 0xad, 0xbe, 0xef, 0x92, 0x01, 0x02];
+    assert_eq!(unpack_slice::<Signed>(&packed).unwrap(), signed);
+
+    let err =
+        unpack_slice::<Signed>(&[0x92, 0xc4, 0x02, 0xde, 0xad, 0x92, 0x01, 0x02]).unwrap_err();
+    assert!(matches!(
+        err,
+        UnpackErr::InvalidLength {
+            expected: 4,
+            found: 2
+        }
+    ));
+}
@@ -0,0 +1,88 @@
+use msgpck::{pack_vec, unpack_slice, ByteBuf, Bytes, MsgPack, MsgUnpack, UnpackErr};
+
+#[derive(Debug, PartialEq, MsgPack, MsgUnpack)]
+pub struct Firmware {
+    pub version: u16,
+    #[msgpck(bytes)]
+    pub image: Vec<u8>,
+    #[msgpck(bytes)]
+    pub hash: [u8; 4],
+    #[msgpck(bytes)]
+    pub signature: heapless::Vec<u8, 8>,
+}
+
+#[derive(Debug, PartialEq, MsgPack, MsgUnpack)]
+pub enum Blob<'a> {
+    Borrowed(Bytes<'a>),
+    Owned { id: u8, data: ByteBuf },
+}
+
+#[test]
+fn bytes_field() {
+    let firmware = Firmware {
+        version: 3,
+        image: vec![0xaa; 20],
+        hash: [1, 2, 3, 4],
+        signature: heapless::Vec::from_slice(&[5, 6]).unwrap(),
+    };
+
+    let packed = pack_vec(&firmware).unwrap();
+    let packed_iter: Vec<u8> = firmware
+        .pack()
+        .flat_map(|p| p.as_bytes().to_vec())
+        .collect();
+    assert_eq!(packed, packed_iter);
+
+    let mut expected = vec![0x94, 0x03, 0xc4, 20];
+    expected.extend_from_slice(&[0xaa; 20]);
+    expected.extend_from_slice(&[0xc4, 4, 1, 2, 3, 4, 0xc4, 2, 5, 6]);
+    assert_eq!(packed, expected);
+
+    assert_eq!(unpack_slice::<Firmware>(&packed).unwrap(), firmware);
+}
+
+#[test]
+fn bytes_field_from_array() {
+    // packed without #[msgpck(bytes)]
+    let packed = pack_vec(&(3u16, vec![0xaau8; 20], [1u8, 2, 3, 4], vec![5u8, 6])).unwrap();
+
+    let firmware: Firmware = unpack_slice(&packed).unwrap();
+    assert_eq!(firmware.image, [0xaa; 20]);
+    assert_eq!(firmware.hash, [1, 2, 3, 4]);
+    assert_eq!(firmware.signature, [5, 6]);
+
+    // the signature doesn't fit
+    let packed = pack_vec(&(3u16, vec![0xaau8; 20], [1u8, 2, 3, 4], vec![0u8; 9])).unwrap();
+    let err = unpack_slice::<Firmware>(&packed).unwrap_err();
+    assert!(matches!(err, UnpackErr::BufferOverflow));
+}
+
+#[test]
+fn byte_wrappers() {
+    let data = [0x10, 0x20, 0x30];
+
+    let blob = Blob::Borrowed(Bytes(&data));
+    let packed = pack_vec(&blob).unwrap();
+    assert_eq!(unpack_slice::<Blob>(&packed).unwrap(), blob);
+
+    let blob = Blob::Owned {
+        id: 1,
+        data: ByteBuf(data.to_vec()),
+    };
+    let packed = pack_vec(&blob).unwrap();
+    assert_eq!(unpack_slice::<Blob>(&packed).unwrap(), blob);
+
+    let buf = msgpck::heapless08::ByteBuf::<4>(heapless::Vec::from_slice(&data).unwrap());
+    let packed = pack_vec(&buf).unwrap();
+    assert_eq!(packed, [0xc4, 0x03, 0x10, 0x20, 0x30]);
+    assert_eq!(
+        unpack_slice::<msgpck::heapless08::ByteBuf<4>>(&packed).unwrap(),
+        buf
+    );
+
+    // borrowed bytes can only be unpacked from a bin
+    let packed = pack_vec(&data.to_vec()).unwrap();
+    let err = unpack_slice::<Bytes>(&packed).unwrap_err();
+    assert!(matches!(err, UnpackErr::WrongMarker { .. }));
+    assert_eq!(unpack_slice::<ByteBuf>(&packed).unwrap().0, data);
+}
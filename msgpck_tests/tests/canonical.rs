@@ -0,0 +1,148 @@
+use std::collections::HashMap;
+
+use msgpck::{
+    pack_vec, unpack_slice_canonical, CanonicalMap, MsgPack, MsgUnpack, Timestamp, UnpackErr,
+};
+use msgpck_tests::CStyleEnum;
+use quickcheck_macros::quickcheck;
+
+#[derive(Debug, PartialEq, MsgPack, MsgUnpack)]
+pub struct Status {
+    #[msgpck(key = 1)]
+    pub id: u32,
+    #[msgpck(key = 2)]
+    pub name: String,
+}
+
+/// A [Status] where the fields aren't declared in the order of their keys.
+#[derive(Debug, PartialEq, MsgPack, MsgUnpack)]
+pub struct ReorderedStatus {
+    #[msgpck(key = 2)]
+    pub name: String,
+    #[msgpck(key = 1)]
+    pub id: u32,
+}
+
+fn assert_non_canonical<'a, T: MsgUnpack<'a> + std::fmt::Debug>(bytes: &'a [u8]) {
+    let err = unpack_slice_canonical::<T>(bytes).unwrap_err();
+    assert!(
+        matches!(err, UnpackErr::NonCanonical),
+        "expected NonCanonical, got {err:?}"
+    );
+}
+
+#[test]
+fn canonical_values() {
+    assert_eq!(unpack_slice_canonical::<u16>(&[0xcc, 0x80]).unwrap(), 0x80);
+    assert_eq!(unpack_slice_canonical::<i16>(&[0xd0, 0x80]).unwrap(), -128);
+    assert_eq!(
+        unpack_slice_canonical::<&str>(&[0xa2, b'h', b'i']).unwrap(),
+        "hi"
+    );
+
+    let status = Status {
+        id: 7,
+        name: "sensor".into(),
+    };
+    assert_eq!(
+        unpack_slice_canonical::<Status>(&pack_vec(&status).unwrap()).unwrap(),
+        status
+    );
+
+    // keyed fields are packed in the order of their keys
+    let status = ReorderedStatus {
+        name: "sensor".into(),
+        id: 7,
+    };
+    assert_eq!(
+        unpack_slice_canonical::<ReorderedStatus>(&pack_vec(&status).unwrap()).unwrap(),
+        status
+    );
+
+    let map = CanonicalMap(HashMap::<u8, u8>::from([(1, 2), (3, 4)]));
+    assert_eq!(
+        unpack_slice_canonical::<HashMap<u8, u8>>(&pack_vec(&map).unwrap()).unwrap(),
+        map.0
+    );
+}
+
+#[test]
+fn non_canonical_ints() {
+    // 7 as a u8, u16 and i16
+    assert_non_canonical::<u8>(&[0xcc, 0x07]);
+    assert_non_canonical::<u32>(&[0xcd, 0x00, 0x07]);
+    assert_non_canonical::<i32>(&[0xd1, 0x00, 0x07]);
+
+    // -1 as an i8 and 0x80 as an i16
+    assert_non_canonical::<i8>(&[0xd0, 0xff]);
+    assert_non_canonical::<i64>(&[0xd1, 0x00, 0x80]);
+}
+
+/// Test that an enum discriminant is only accepted in its shortest form.
+#[quickcheck]
+fn enum_discriminant(e: CStyleEnum) {
+    let discriminant = e as i8;
+
+    // the discriminant packed as the smallest msgpack int that fits it
+    let msgpacked = pack_vec(&discriminant).unwrap();
+    assert_eq!(unpack_slice_canonical::<CStyleEnum>(&msgpacked).unwrap(), e);
+
+    // the discriminant as an i8, which is only the shortest form below -32
+    let msgpacked = [0xd0, discriminant as u8];
+    if discriminant < -32 {
+        assert_eq!(unpack_slice_canonical::<CStyleEnum>(&msgpacked).unwrap(), e);
+    } else {
+        assert_non_canonical::<CStyleEnum>(&msgpacked);
+    }
+}
+
+#[test]
+fn non_canonical_lengths() {
+    // "hi" as a str8
+    assert_non_canonical::<&str>(&[0xd9, 0x02, b'h', b'i']);
+
+    // [1, 2] as a bin16
+    assert_non_canonical::<&[u8]>(&[0xc5, 0x00, 0x02, 1, 2]);
+
+    // [1] as an array16
+    assert_non_canonical::<Vec<u8>>(&[0xdc, 0x00, 0x01, 0x01]);
+
+    // {1: 2} as a map16
+    assert_non_canonical::<HashMap<u8, u8>>(&[0xde, 0x00, 0x01, 0x01, 0x02]);
+}
+
+#[test]
+fn duplicate_keys() {
+    assert_non_canonical::<HashMap<u8, u8>>(&[0x82, 0x01, 0x02, 0x01, 0x03]);
+
+    // {1: 7, 1: 8}
+    assert_non_canonical::<Status>(&[0x82, 0x01, 0x07, 0x01, 0x08]);
+}
+
+#[test]
+fn unsorted_keys() {
+    // {3: 4, 1: 2}
+    assert_non_canonical::<HashMap<u8, u8>>(&[0x82, 0x03, 0x04, 0x01, 0x02]);
+
+    // {2: "x", 1: 7}
+    assert_non_canonical::<Status>(&[0x82, 0x02, 0xa1, b'x', 0x01, 0x07]);
+
+    // {1: 7, 3: 0, 2: "x"}, where the unknown key 3 is out of order
+    assert_non_canonical::<Status>(&[0x83, 0x01, 0x07, 0x03, 0x00, 0x02, 0xa1, b'x']);
+}
+
+#[test]
+fn non_canonical_timestamps() {
+    // 1970-01-01T00:00:01Z as a timestamp 64 and a timestamp 96
+    assert_non_canonical::<Timestamp>(&[0xd7, 0xff, 0, 0, 0, 0, 0, 0, 0, 0x01]);
+    assert_non_canonical::<Timestamp>(&[0xc7, 0x0c, 0xff, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x01]);
+
+    // as a timestamp 32 in an ext8
+    assert_non_canonical::<Timestamp>(&[0xc7, 0x04, 0xff, 0, 0, 0, 0x01]);
+
+    // as epoch seconds and as a string
+    assert_non_canonical::<Timestamp>(&[0x01]);
+    assert_non_canonical::<chrono::DateTime<chrono::Utc>>(&[0x01]);
+    let packed = pack_vec(&"1970-01-01T00:00:01Z").unwrap();
+    assert_non_canonical::<chrono::DateTime<chrono::Utc>>(&packed);
+}
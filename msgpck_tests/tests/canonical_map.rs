@@ -0,0 +1,99 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+
+use msgpck::{
+    pack_vec, unpack_slice, unpack_slice_canonical, CanonicalMap, CanonicalSet, MsgPack, MsgUnpack,
+};
+
+#[derive(Debug, PartialEq, MsgPack, MsgUnpack)]
+pub struct Cache {
+    pub entries: CanonicalMap<HashMap<String, u32>>,
+}
+
+#[test]
+fn canonical_map_is_deterministic() {
+    let keys = [
+        "one", "two", "three", "four", "five", "six", "seven", "eight",
+    ];
+
+    // maps with the same entries, but inserted in different orders
+    let forward: HashMap<String, u32> = keys
+        .iter()
+        .map(|k| (k.to_string(), k.len() as u32))
+        .collect();
+    let backward: HashMap<String, u32> = keys
+        .iter()
+        .rev()
+        .map(|k| (k.to_string(), k.len() as u32))
+        .collect();
+
+    let packed = pack_vec(&CanonicalMap(forward)).unwrap();
+    assert_eq!(packed, pack_vec(&CanonicalMap(backward)).unwrap());
+
+    // the keys are sorted by their packed bytes, which for strings puts shorter ones first
+    let mut bytes = &packed[..];
+    let len = msgpck::helpers::unpack_map_header(&mut bytes).unwrap();
+    let mut packed_keys = vec![];
+    for _ in 0..len {
+        packed_keys.push(<&str>::unpack(&mut bytes).unwrap());
+        u32::unpack(&mut bytes).unwrap();
+    }
+    let mut sorted_keys = keys.to_vec();
+    sorted_keys.sort_by_key(|k| (k.len(), *k));
+    assert_eq!(packed_keys, sorted_keys);
+
+    let unpacked: CanonicalMap<HashMap<String, u32>> = unpack_slice(&packed).unwrap();
+    assert_eq!(unpacked.len(), keys.len());
+    assert_eq!(unpacked["three"], 5);
+}
+
+#[test]
+fn canonical_map_field() {
+    let cache = Cache {
+        entries: CanonicalMap([("a".to_string(), 1), ("b".to_string(), 2)].into()),
+    };
+
+    let packed = pack_vec(&cache).unwrap();
+    assert_eq!(packed, [0x91, 0x82, 0xa1, b'a', 0x01, 0xa1, b'b', 0x02]);
+    assert_eq!(unpack_slice::<Cache>(&packed).unwrap(), cache);
+}
+
+#[test]
+fn canonical_set_is_deterministic() {
+    let forward: HashSet<u16> = (0..64).map(|i| i * 100).collect();
+    let backward: HashSet<u16> = (0..64).rev().map(|i| i * 100).collect();
+
+    let packed = pack_vec(&CanonicalSet(forward)).unwrap();
+    assert_eq!(packed, pack_vec(&CanonicalSet(backward)).unwrap());
+
+    // sorted by the packed bytes, so the integers are in ascending order
+    let sorted: Vec<u16> = (0..64).map(|i| i * 100).collect();
+    assert_eq!(packed, pack_vec(&sorted).unwrap());
+
+    let unpacked: CanonicalSet<HashSet<u16>> = unpack_slice(&packed).unwrap();
+    assert_eq!(unpacked.len(), 64);
+}
+
+#[test]
+fn canonical_btree_map() {
+    let map: BTreeMap<i8, &str> = [(-1, "a"), (1, "b")].into();
+
+    // a BTreeMap is packed in the order of its keys, where -1 (0xff) comes before 1
+    let packed = pack_vec(&map).unwrap();
+    assert_eq!(packed, [0x82, 0xff, 0xa1, b'a', 0x01, 0xa1, b'b']);
+    assert!(unpack_slice_canonical::<BTreeMap<i8, &str>>(&packed).is_err());
+
+    let packed = pack_vec(&CanonicalMap(map.clone())).unwrap();
+    assert_eq!(packed, [0x82, 0x01, 0xa1, b'b', 0xff, 0xa1, b'a']);
+    assert_eq!(
+        unpack_slice_canonical::<BTreeMap<i8, &str>>(&packed).unwrap(),
+        map
+    );
+
+    let set: BTreeSet<i8> = [-1, 1].into();
+    let packed = pack_vec(&CanonicalSet(set.clone())).unwrap();
+    assert_eq!(packed, [0x92, 0x01, 0xff]);
+    assert_eq!(
+        unpack_slice_canonical::<CanonicalSet<BTreeSet<i8>>>(&packed).unwrap(),
+        CanonicalSet(set)
+    );
+}
@@ -0,0 +1,53 @@
+use std::{borrow::Cow, collections::BinaryHeap, rc::Rc, sync::Arc};
+
+use msgpck::{pack_vec, unpack_slice, MsgPack, MsgUnpack};
+
+#[derive(Debug, PartialEq, MsgPack, MsgUnpack)]
+pub struct Message<'a> {
+    pub topic: Cow<'a, str>,
+    pub payload: Cow<'a, [u8]>,
+}
+
+#[test]
+fn binary_heap() {
+    let heap = BinaryHeap::from([3, 1, 4, 1, 5]);
+
+    let packed = pack_vec(&heap).unwrap();
+    let unpacked: BinaryHeap<i32> = unpack_slice(&packed).unwrap();
+    assert_eq!(unpacked.into_sorted_vec(), [1, 1, 3, 4, 5]);
+}
+
+#[test]
+fn shared_pointers() {
+    let value = (String::from("shared"), 7u8);
+    let packed = pack_vec(&value).unwrap();
+
+    assert_eq!(pack_vec(&Rc::new(value.clone())).unwrap(), packed);
+    assert_eq!(pack_vec(&Arc::new(value.clone())).unwrap(), packed);
+    assert_eq!(*unpack_slice::<Rc<(String, u8)>>(&packed).unwrap(), value);
+    assert_eq!(*unpack_slice::<Arc<(String, u8)>>(&packed).unwrap(), value);
+
+    // unsized values can also be packed
+    let s: Rc<str> = "shared".into();
+    assert_eq!(pack_vec(&s).unwrap(), pack_vec(&"shared").unwrap());
+}
+
+#[test]
+fn cow_borrows() {
+    let message = Message {
+        topic: Cow::Owned("sensors/1".into()),
+        payload: Cow::Owned(vec![1, 2, 3]),
+    };
+
+    let packed = pack_vec(&message).unwrap();
+    let unpacked: Message = unpack_slice(&packed).unwrap();
+    assert_eq!(unpacked, message);
+    assert!(matches!(unpacked.topic, Cow::Borrowed(_)));
+    assert!(matches!(unpacked.payload, Cow::Borrowed(_)));
+
+    // bytes packed as an array can't be borrowed
+    let packed = pack_vec(&("sensors/1", vec![1u8, 2, 3])).unwrap();
+    let unpacked: Message = unpack_slice(&packed).unwrap();
+    assert_eq!(unpacked, message);
+    assert!(matches!(unpacked.payload, Cow::Owned(_)));
+}
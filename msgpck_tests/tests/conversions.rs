@@ -0,0 +1,152 @@
+use msgpck::{pack_vec, unpack_slice, ByteBuf, MsgPack, MsgUnpack, UnpackErr};
+use msgpck_tests::{test_pack_unpack, NotPack};
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, MsgPack, MsgUnpack)]
+#[msgpck(transparent)]
+#[serde(transparent)]
+pub struct UserId {
+    pub id: u64,
+}
+
+#[derive(Clone, Debug, PartialEq, MsgPack, MsgUnpack)]
+#[msgpck(transparent)]
+pub struct Label<'a>(#[msgpck(skip)] NotPack, &'a str);
+
+/// A validated percentage, which is packed as a plain integer.
+#[derive(Clone, Debug, PartialEq, MsgPack, MsgUnpack)]
+#[msgpck(try_from = "u8", into = "u8")]
+pub struct Percent(u8);
+
+impl TryFrom<u8> for Percent {
+    type Error = &'static str;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        if value > 100 {
+            return Err("percentage is larger than 100");
+        }
+        Ok(Percent(value))
+    }
+}
+
+impl From<Percent> for u8 {
+    fn from(percent: Percent) -> Self {
+        percent.0
+    }
+}
+
+#[derive(Debug, PartialEq, MsgPack, MsgUnpack)]
+pub struct LocationDto {
+    pub name: String,
+    pub lat: f64,
+    pub lon: f64,
+}
+
+#[derive(Clone, Debug, PartialEq, MsgPack, MsgUnpack)]
+#[msgpck(from = "LocationDto", into = "LocationDto")]
+pub enum Location {
+    Named(String),
+    Coordinates(f64, f64),
+}
+
+impl From<LocationDto> for Location {
+    fn from(dto: LocationDto) -> Self {
+        if dto.name.is_empty() {
+            Location::Coordinates(dto.lat, dto.lon)
+        } else {
+            Location::Named(dto.name)
+        }
+    }
+}
+
+impl From<Location> for LocationDto {
+    fn from(location: Location) -> Self {
+        match location {
+            Location::Named(name) => LocationDto {
+                name,
+                lat: 0.0,
+                lon: 0.0,
+            },
+            Location::Coordinates(lat, lon) => LocationDto {
+                name: String::new(),
+                lat,
+                lon,
+            },
+        }
+    }
+}
+
+/// A large value, which is packed by converting it into a [ByteBuf].
+#[derive(Clone, MsgPack)]
+#[msgpck(into = "ByteBuf")]
+pub struct Firmware {
+    pub image: Vec<u8>,
+}
+
+impl From<Firmware> for ByteBuf {
+    fn from(firmware: Firmware) -> Self {
+        ByteBuf(firmware.image)
+    }
+}
+
+/// Pack using [MsgPack::pack] instead of [MsgPack::pack_with_writer].
+fn pack_with_iterator(v: &impl MsgPack) -> Vec<u8> {
+    v.pack().flat_map(|p| p.as_bytes().to_vec()).collect()
+}
+
+#[test]
+fn transparent() {
+    test_pack_unpack(&UserId { id: 0xabcd });
+
+    let label = Label(NotPack, "a label that is longer than eight bytes");
+    let packed = pack_vec(&label).unwrap();
+    assert_eq!(packed, pack_vec(&label.1).unwrap());
+    assert_eq!(packed, pack_with_iterator(&label));
+
+    let unpacked: Label = unpack_slice(&packed).unwrap();
+    assert_eq!(unpacked, label);
+}
+
+#[test]
+fn try_from_into() {
+    let packed = pack_vec(&Percent(42)).unwrap();
+    assert_eq!(packed, [42]);
+    assert_eq!(packed, pack_with_iterator(&Percent(42)));
+    assert_eq!(unpack_slice::<Percent>(&packed).unwrap(), Percent(42));
+
+    let err = unpack_slice::<Percent>(&[101]).unwrap_err();
+    let UnpackErr::Conversion(err) = err else {
+        panic!("expected a conversion error, got {err:?}");
+    };
+    assert_eq!(err.to_string(), "percentage is larger than 100");
+}
+
+#[test]
+fn from_into() {
+    for location in [
+        Location::Named("a place with a long name".into()),
+        Location::Coordinates(57.7, 11.97),
+    ] {
+        let packed = pack_vec(&location).unwrap();
+        assert_eq!(
+            packed,
+            pack_vec(&LocationDto::from(location.clone())).unwrap()
+        );
+        assert_eq!(packed, pack_with_iterator(&location));
+
+        let unpacked: Location = unpack_slice(&packed).unwrap();
+        assert_eq!(unpacked, location);
+    }
+}
+
+#[test]
+fn into_large_value() {
+    let firmware = Firmware {
+        image: vec![0xab; 1 << 20],
+    };
+
+    // the converted value is only packed once, and not once per piece
+    let packed = pack_with_iterator(&firmware);
+    assert_eq!(packed.len(), 5 + (1 << 20));
+    assert_eq!(packed, pack_vec(&firmware).unwrap());
+}
@@ -0,0 +1,118 @@
+use msgpck::{
+    pack_vec, unpack_slice, unpack_slice_with_context, MsgPack, MsgUnpack, PathSegment, UnpackErr,
+};
+
+#[derive(Debug, PartialEq, MsgPack, MsgUnpack)]
+pub struct Sensor {
+    pub id: u8,
+    pub readings: Vec<Reading>,
+}
+
+#[derive(Debug, PartialEq, MsgPack, MsgUnpack)]
+pub enum Reading {
+    Temperature { celsius: u8, sensor: u8 },
+    Humidity(u8, u8),
+}
+
+/// A [Sensor] where one of the readings is too large to fit in a `u8`.
+#[derive(MsgPack)]
+pub struct BadSensor {
+    pub id: u8,
+    pub readings: Vec<BadReading>,
+}
+
+#[derive(MsgPack)]
+pub enum BadReading {
+    Temperature { celsius: u8, sensor: u8 },
+    Humidity(u8, u16),
+}
+
+#[test]
+fn error_path() {
+    let sensor = BadSensor {
+        id: 1,
+        readings: vec![
+            BadReading::Temperature {
+                celsius: 20,
+                sensor: 2,
+            },
+            BadReading::Humidity(40, 1000),
+        ],
+    };
+    let packed = pack_vec(&sensor).unwrap();
+    let err = unpack_slice_with_context::<Sensor>(&packed).unwrap_err();
+
+    assert!(matches!(err.error, UnpackErr::IntTooBig(_)));
+    assert_eq!(
+        err.path(),
+        [
+            PathSegment::Type("Sensor"),
+            PathSegment::Field("readings"),
+            PathSegment::Index(1),
+            PathSegment::Type("Reading"),
+            PathSegment::Variant("Humidity"),
+            PathSegment::Index(1),
+        ]
+    );
+
+    // the error is reported at the start of the value that was too large, i.e. the u16 1000
+    assert_eq!(err.offset, packed.len() - 3);
+    let expected = format!(
+        "Sensor.readings[1]::Humidity[1] (byte {}): Encounted an integer value that was too large.",
+        packed.len() - 3
+    );
+    assert!(err.to_string().starts_with(&expected), "{err}");
+}
+
+#[test]
+fn error_variant_is_kept() {
+    // the error isn't wrapped, even when it occurs in a nested value
+    let packed = pack_vec(&(1u8, "readings")).unwrap();
+    let err = unpack_slice::<Sensor>(&packed).unwrap_err();
+    assert!(matches!(err, UnpackErr::WrongMarker { .. }));
+
+    let err = unpack_slice_with_context::<u8>(&[0xc0]).unwrap_err();
+    assert!(matches!(err.error, UnpackErr::WrongMarker { .. }));
+    assert!(err.path().is_empty());
+    assert_eq!(err.offset, 0);
+    assert!(err.to_string().starts_with("byte 0: Wrong marker"), "{err}");
+}
+
+#[test]
+fn error_offset() {
+    // [0x10, 0x20, "x", 0x40], where the error is reported at the start of "x"
+    let bytes = [0x94, 0x10, 0x20, 0xa1, b'x', 0x40];
+    let err = unpack_slice_with_context::<Vec<u8>>(&bytes).unwrap_err();
+    assert_eq!(err.offset, 3);
+    assert!(matches!(err.error, UnpackErr::WrongMarker { .. }));
+    assert_eq!(err.path(), [PathSegment::Index(2)]);
+
+    let err = unpack_slice_with_context::<u8>(&[0x01, 0x02]).unwrap_err();
+    assert_eq!(err.offset, 1);
+    assert!(matches!(err.error, UnpackErr::TrailingBytes(1)));
+}
+
+#[test]
+fn error_offset_is_value_start() {
+    fn offset<'a, T: MsgUnpack<'a> + std::fmt::Debug>(bytes: &'a [u8]) -> usize {
+        unpack_slice_with_context::<(u8, u8, T)>(bytes)
+            .unwrap_err()
+            .offset
+    }
+
+    // [1, 2, nil], where the decoders read different amounts of the nil before failing
+    let bytes = [0x93, 0x01, 0x02, 0xc0];
+    assert_eq!(offset::<u8>(&bytes), 3);
+    assert_eq!(offset::<&str>(&bytes), 3);
+    assert_eq!(offset::<bool>(&bytes), 3);
+    assert_eq!(offset::<f32>(&bytes), 3);
+    assert_eq!(offset::<Vec<u8>>(&bytes), 3);
+
+    // [1, 2, 1000], which is only too large once it has been read
+    let bytes = [0x93, 0x01, 0x02, 0xcd, 0x03, 0xe8];
+    assert_eq!(offset::<u8>(&bytes), 3);
+
+    // [1, 2, "x"]
+    let bytes = [0x93, 0x01, 0x02, 0xa1, b'x'];
+    assert_eq!(offset::<Option<f64>>(&bytes), 3);
+}
@@ -0,0 +1,228 @@
+use msgpck::{
+    pack_vec, unpack_slice, unpack_slice_canonical, unpack_slice_with_context, MsgPack, MsgUnpack,
+    PathSegment, UnpackErr,
+};
+use msgpck_tests::test_pack_unpack;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, MsgPack, MsgUnpack)]
+pub struct Header {
+    pub version: u8,
+    pub id: u32,
+}
+
+#[derive(Clone, Debug, PartialEq, MsgPack, MsgUnpack)]
+pub struct Message {
+    #[msgpck(flatten)]
+    pub header: Header,
+    pub body: String,
+}
+
+#[derive(Clone, Debug, PartialEq, MsgPack, MsgUnpack)]
+pub struct Envelope(#[msgpck(flatten)] Message, u64);
+
+/// The serde equivalent of [Envelope], without any flattening.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, MsgPack, MsgUnpack)]
+pub struct FlatEnvelope {
+    pub version: u8,
+    pub id: u32,
+    pub body: String,
+    pub timestamp: u64,
+}
+
+/// Pack using [MsgPack::pack] instead of [MsgPack::pack_with_writer].
+fn pack_with_iterator(v: &impl MsgPack) -> Vec<u8> {
+    v.pack().flat_map(|p| p.as_bytes().to_vec()).collect()
+}
+
+#[test]
+fn flatten() {
+    let envelope = Envelope(
+        Message {
+            header: Header {
+                version: 1,
+                id: 0xbeef,
+            },
+            body: "hello".into(),
+        },
+        1_700_000_000,
+    );
+    let flat = FlatEnvelope {
+        version: 1,
+        id: 0xbeef,
+        body: "hello".into(),
+        timestamp: 1_700_000_000,
+    };
+    test_pack_unpack(&flat);
+
+    let packed = pack_vec(&envelope).unwrap();
+    assert_eq!(packed, pack_vec(&flat).unwrap());
+    assert_eq!(packed, pack_with_iterator(&envelope));
+    assert_eq!(unpack_slice::<Envelope>(&packed).unwrap(), envelope);
+}
+
+#[test]
+fn flatten_field_count() {
+    // [version, id] is missing the body
+    let packed = pack_vec(&Header { version: 1, id: 2 }).unwrap();
+    let err = unpack_slice::<Message>(&packed).unwrap_err();
+    assert!(matches!(
+        err,
+        UnpackErr::MissingFields {
+            got: 2,
+            expected: 3
+        }
+    ));
+}
+
+#[derive(Clone, Debug, Default, PartialEq, MsgPack, MsgUnpack)]
+pub struct KeyedHeader {
+    #[msgpck(key = 0)]
+    pub version: u8,
+    #[msgpck(key = 1, skip_if = "Option::is_none")]
+    pub trace: Option<u32>,
+}
+
+#[derive(Clone, Debug, PartialEq, MsgPack, MsgUnpack)]
+pub struct KeyedMessage {
+    #[msgpck(key = 3)]
+    pub body: String,
+    #[msgpck(flatten)]
+    pub header: KeyedHeader,
+    #[msgpck(key = 2)]
+    pub id: u32,
+}
+
+/// A [KeyedMessage] flattened once more.
+#[derive(Clone, Debug, PartialEq, MsgPack, MsgUnpack)]
+pub struct KeyedEnvelope {
+    #[msgpck(flatten)]
+    pub message: KeyedMessage,
+    #[msgpck(key = 10)]
+    pub timestamp: u64,
+}
+
+/// The equivalent of [KeyedEnvelope], without any flattening.
+#[derive(Clone, Debug, PartialEq, MsgPack, MsgUnpack)]
+pub struct FlatKeyedEnvelope {
+    #[msgpck(key = 0)]
+    pub version: u8,
+    #[msgpck(key = 1, skip_if = "Option::is_none")]
+    pub trace: Option<u32>,
+    #[msgpck(key = 2)]
+    pub id: u32,
+    #[msgpck(key = 3)]
+    pub body: String,
+    #[msgpck(key = 10)]
+    pub timestamp: u64,
+}
+
+#[test]
+fn flatten_keyed() {
+    for trace in [None, Some(7)] {
+        let envelope = KeyedEnvelope {
+            message: KeyedMessage {
+                body: "hello".into(),
+                header: KeyedHeader { version: 1, trace },
+                id: 0xbeef,
+            },
+            timestamp: 1_700_000_000,
+        };
+        let flat = FlatKeyedEnvelope {
+            version: 1,
+            trace,
+            id: 0xbeef,
+            body: "hello".into(),
+            timestamp: 1_700_000_000,
+        };
+
+        // the flattened entries are merged with the other entries, in the order of their keys
+        let packed = pack_vec(&envelope).unwrap();
+        assert_eq!(packed, pack_vec(&flat).unwrap());
+        assert_eq!(packed, pack_with_iterator(&envelope));
+        assert_eq!(unpack_slice::<KeyedEnvelope>(&packed).unwrap(), envelope);
+        assert_eq!(
+            unpack_slice_canonical::<KeyedEnvelope>(&packed).unwrap(),
+            envelope
+        );
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, MsgPack, MsgUnpack)]
+pub struct Trailer {
+    #[msgpck(key = 4)]
+    pub checksum: u16,
+    #[msgpck(key = 10)]
+    pub version: u8,
+}
+
+/// A struct where the keys of the flattened [Trailer] are higher than, and in between, its own.
+#[derive(Clone, Debug, PartialEq, MsgPack, MsgUnpack)]
+pub struct TrailedMessage {
+    #[msgpck(key = 0)]
+    pub id: u8,
+    #[msgpck(flatten)]
+    pub trailer: Trailer,
+    #[msgpck(key = 5)]
+    pub body: u8,
+}
+
+#[test]
+fn flatten_keyed_higher_keys() {
+    let message = TrailedMessage {
+        id: 1,
+        trailer: Trailer {
+            checksum: 3,
+            version: 2,
+        },
+        body: 4,
+    };
+
+    // {0: 1, 4: 3, 5: 4, 10: 2}
+    let packed = pack_vec(&message).unwrap();
+    assert_eq!(
+        packed,
+        [0x84, 0x00, 0x01, 0x04, 0x03, 0x05, 0x04, 0x0a, 0x02]
+    );
+    assert_eq!(packed, pack_with_iterator(&message));
+    assert_eq!(
+        unpack_slice_canonical::<TrailedMessage>(&packed).unwrap(),
+        message
+    );
+}
+
+#[test]
+fn flatten_keyed_any_order() {
+    // {3: "hi", 9: nil, 0: 2, 2: 5}, where 9 is unknown and the trace and timestamp are missing
+    let packed = [
+        0x84, 0x03, 0xa2, b'h', b'i', 0x09, 0xc0, 0x00, 0x02, 0x02, 0x05,
+    ];
+    let envelope = unpack_slice::<KeyedEnvelope>(&packed).unwrap();
+    assert_eq!(
+        envelope,
+        KeyedEnvelope {
+            message: KeyedMessage {
+                body: "hi".into(),
+                header: KeyedHeader {
+                    version: 2,
+                    trace: None
+                },
+                id: 5,
+            },
+            timestamp: 0,
+        }
+    );
+
+    // {1: "x"}, where the trace of the flattened header has the wrong type
+    let err = unpack_slice_with_context::<KeyedEnvelope>(&[0x81, 0x01, 0xa1, b'x']).unwrap_err();
+    assert!(matches!(err.error, UnpackErr::WrongMarker { .. }));
+    assert_eq!(
+        err.path(),
+        [
+            PathSegment::Type("KeyedEnvelope"),
+            PathSegment::Field("message"),
+            PathSegment::Field("header"),
+            PathSegment::Field("trace"),
+        ]
+    );
+}
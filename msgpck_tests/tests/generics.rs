@@ -0,0 +1,83 @@
+use core::fmt::Debug;
+use core::marker::PhantomData;
+use msgpck::{pack_vec, unpack_slice, MsgPack, MsgUnpack};
+use msgpck_tests::{test_pack_unpack, NotPack};
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, MsgPack, MsgUnpack)]
+pub struct Buf<const N: usize> {
+    pub len: u8,
+    pub data: Vec<u16>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, MsgPack, MsgUnpack)]
+pub struct Pair<A, B>
+where
+    A: Debug,
+    B: Clone,
+{
+    pub a: A,
+    pub b: B,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, MsgPack, MsgUnpack)]
+pub enum Either<L, R, const N: usize>
+where
+    L: Debug,
+{
+    Left(L),
+    Right { right: R, n: u8 },
+}
+
+/// A struct that only needs `T` at the type level, and therefore overrides the generated bounds.
+#[derive(Clone, Debug, PartialEq, MsgPack, MsgUnpack)]
+#[msgpck(bound = "")]
+pub struct Tagged<T> {
+    pub id: u32,
+    #[msgpck(skip)]
+    pub tag: PhantomData<T>,
+}
+
+#[derive(Clone, Debug, PartialEq, MsgPack, MsgUnpack)]
+#[msgpck(pack(bound = "T: MsgPack"))]
+#[msgpck(unpack(bound = "T: MsgUnpack<'_msgpck> + Default"))]
+pub enum Borrowed<'a, T> {
+    Text(&'a str),
+    Value(T),
+}
+
+#[test]
+fn const_generics() {
+    test_pack_unpack(&Buf::<8> {
+        len: 2,
+        data: vec![0xab, 0xcdef],
+    });
+    test_pack_unpack(&Either::<u8, String, 4>::Left(3));
+    test_pack_unpack(&Either::<u8, String, 4>::Right {
+        right: "right".into(),
+        n: 7,
+    });
+}
+
+#[test]
+fn where_clauses() {
+    test_pack_unpack(&Pair {
+        a: -5i32,
+        b: "b".to_string(),
+    });
+}
+
+#[test]
+fn custom_bounds() {
+    let tagged = Tagged::<NotPack> {
+        id: 42,
+        tag: PhantomData,
+    };
+    let packed = pack_vec(&tagged).unwrap();
+    assert_eq!(unpack_slice::<Tagged<NotPack>>(&packed).unwrap(), tagged);
+
+    for borrowed in [Borrowed::Text("text"), Borrowed::Value(-1i64)] {
+        let packed = pack_vec(&borrowed).unwrap();
+        assert_eq!(unpack_slice::<Borrowed<i64>>(&packed).unwrap(), borrowed);
+    }
+}
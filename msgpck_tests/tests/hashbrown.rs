@@ -0,0 +1,38 @@
+use msgpck::{pack_vec, unpack_slice, CanonicalMap, CanonicalSet};
+
+#[test]
+fn hashbrown_map() {
+    let map: hashbrown::HashMap<String, u32> =
+        [("one".into(), 1), ("two".into(), 2), ("three".into(), 3)].into();
+
+    let packed = pack_vec(&map).unwrap();
+    assert_eq!(
+        unpack_slice::<hashbrown::HashMap<String, u32>>(&packed).unwrap(),
+        map
+    );
+
+    // same encoding as std
+    let std_map: std::collections::HashMap<String, u32> = unpack_slice(&packed).unwrap();
+    assert_eq!(std_map.len(), 3);
+    assert_eq!(
+        pack_vec(&CanonicalMap(map)).unwrap(),
+        pack_vec(&CanonicalMap(std_map)).unwrap()
+    );
+}
+
+#[test]
+fn hashbrown_set() {
+    let set: hashbrown::HashSet<u8> = [1, 2, 3].into();
+
+    let packed = pack_vec(&set).unwrap();
+    assert_eq!(
+        unpack_slice::<hashbrown::HashSet<u8>>(&packed).unwrap(),
+        set
+    );
+
+    let std_set: std::collections::HashSet<u8> = unpack_slice(&packed).unwrap();
+    assert_eq!(
+        pack_vec(&CanonicalSet(set)).unwrap(),
+        pack_vec(&CanonicalSet(std_set)).unwrap()
+    );
+}
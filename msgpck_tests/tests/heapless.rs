@@ -0,0 +1,106 @@
+use heapless::{
+    binary_heap::Max, BinaryHeap, Deque, FnvIndexMap, FnvIndexSet, HistoryBuffer, LinearMap,
+    String, Vec,
+};
+use msgpck::{pack_vec, unpack_slice, UnpackErr};
+use std::collections::BTreeMap;
+
+#[test]
+fn heapless_collections() {
+    let mut deque = Deque::<u8, 4>::new();
+    deque.push_back(2).unwrap();
+    deque.push_front(1).unwrap();
+    let packed = pack_vec(&deque).unwrap();
+    assert_eq!(packed, [0x92, 0x01, 0x02]);
+    let unpacked: Deque<u8, 4> = unpack_slice(&packed).unwrap();
+    assert!(unpacked.iter().eq(deque.iter()));
+
+    let mut map = FnvIndexMap::<String<8>, u16, 4>::new();
+    map.insert("b".try_into().unwrap(), 2).unwrap();
+    map.insert("a".try_into().unwrap(), 1).unwrap();
+    let packed = pack_vec(&map).unwrap();
+    assert_eq!(packed, [0x82, 0xa1, b'b', 0x02, 0xa1, b'a', 0x01]);
+    assert_eq!(unpack_slice::<FnvIndexMap<_, _, 4>>(&packed).unwrap(), map);
+
+    let set = FnvIndexSet::<i32, 4>::from_iter([3, -1, 2]);
+    let packed = pack_vec(&set).unwrap();
+    assert_eq!(unpack_slice::<FnvIndexSet<i32, 4>>(&packed).unwrap(), set);
+
+    let mut heap = BinaryHeap::<u32, Max, 4>::new();
+    for n in [5, 1, 8] {
+        heap.push(n).unwrap();
+    }
+    let packed = pack_vec(&heap).unwrap();
+    let unpacked: BinaryHeap<u32, Max, 4> = unpack_slice(&packed).unwrap();
+    let mut sorted = unpacked.into_vec();
+    sorted.sort();
+    assert_eq!(sorted, [1, 5, 8]);
+
+    // the oldest element is packed first
+    let mut history = HistoryBuffer::<u8, 3>::new();
+    history.extend([1, 2, 3, 4]);
+    let packed = pack_vec(&history).unwrap();
+    assert_eq!(packed, [0x93, 0x02, 0x03, 0x04]);
+    let unpacked: HistoryBuffer<u8, 3> = unpack_slice(&packed).unwrap();
+    assert!(unpacked.oldest_ordered().eq(history.oldest_ordered()));
+}
+
+#[test]
+fn heapless_overflow() {
+    let packed = pack_vec(&[1u8, 2, 3]).unwrap();
+    assert!(matches!(
+        unpack_slice::<Vec<u8, 2>>(&packed),
+        Err(UnpackErr::BufferOverflow)
+    ));
+    assert!(matches!(
+        unpack_slice::<Deque<u8, 2>>(&packed),
+        Err(UnpackErr::BufferOverflow)
+    ));
+    assert!(matches!(
+        unpack_slice::<HistoryBuffer<u8, 2>>(&packed),
+        Err(UnpackErr::BufferOverflow)
+    ));
+    assert!(matches!(
+        unpack_slice::<BinaryHeap<u8, Max, 2>>(&packed),
+        Err(UnpackErr::BufferOverflow)
+    ));
+
+    let packed_map = pack_vec(&BTreeMap::from([(1u8, 1u8), (2, 2), (3, 3)])).unwrap();
+    assert!(matches!(
+        unpack_slice::<LinearMap<u8, u8, 2>>(&packed_map),
+        Err(UnpackErr::BufferOverflow)
+    ));
+    assert!(matches!(
+        unpack_slice::<FnvIndexMap<u8, u8, 2>>(&packed_map),
+        Err(UnpackErr::BufferOverflow)
+    ));
+
+    let packed = pack_vec(&"too long").unwrap();
+    assert!(matches!(
+        unpack_slice::<String<4>>(&packed),
+        Err(UnpackErr::BufferOverflow)
+    ));
+}
+
+#[test]
+fn heapless09() {
+    use heapless09::{Deque, HistoryBuf, Vec};
+
+    let vec = Vec::<u16, 4>::from_slice(&[1, 300]).unwrap();
+    let packed = pack_vec(&vec).unwrap();
+    assert_eq!(
+        packed,
+        pack_vec(&heapless::Vec::<u16, 4>::from_slice(&[1, 300]).unwrap()).unwrap()
+    );
+    assert_eq!(unpack_slice::<Vec<u16, 4>>(&packed).unwrap(), vec);
+
+    let deque: Deque<u16, 2> = unpack_slice(&packed).unwrap();
+    assert!(deque.iter().eq(vec.iter()));
+
+    let mut history = HistoryBuf::<u16, 2>::new();
+    history.extend([0, 1, 300]);
+    assert_eq!(pack_vec(&history).unwrap(), packed);
+
+    let buf = msgpck::heapless09::ByteBuf::<4>(Vec::from_slice(&[1, 2]).unwrap());
+    assert_eq!(pack_vec(&buf).unwrap(), [0xc4, 0x02, 0x01, 0x02]);
+}
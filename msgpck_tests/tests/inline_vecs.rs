@@ -0,0 +1,53 @@
+use arrayvec::{ArrayString, ArrayVec};
+use msgpck::{pack_vec, unpack_slice, UnpackErr};
+use smallvec::SmallVec;
+use tinyvec::TinyVec;
+
+#[test]
+fn inline_vecs() {
+    let v = vec![1u32, 2, 0xffff];
+    let packed = pack_vec(&v).unwrap();
+
+    let array_vec: ArrayVec<u32, 4> = unpack_slice(&packed).unwrap();
+    assert_eq!(array_vec.as_slice(), v);
+    assert_eq!(pack_vec(&array_vec).unwrap(), packed);
+
+    // spills onto the heap
+    let small_vec: SmallVec<[u32; 2]> = unpack_slice(&packed).unwrap();
+    assert!(small_vec.spilled());
+    assert_eq!(small_vec.as_slice(), v);
+    assert_eq!(pack_vec(&small_vec).unwrap(), packed);
+
+    let tiny_vec: TinyVec<[u32; 2]> = unpack_slice(&packed).unwrap();
+    assert!(tiny_vec.is_heap());
+    assert_eq!(tiny_vec.as_slice(), v);
+    assert_eq!(pack_vec(&tiny_vec).unwrap(), packed);
+
+    let tiny_array_vec: tinyvec::ArrayVec<[u32; 3]> = unpack_slice(&packed).unwrap();
+    assert_eq!(tiny_array_vec.as_slice(), v);
+    assert_eq!(pack_vec(&tiny_array_vec).unwrap(), packed);
+
+    let s = ArrayString::<8>::from("hello").unwrap();
+    let packed = pack_vec(&s).unwrap();
+    assert_eq!(packed, pack_vec(&"hello").unwrap());
+    assert_eq!(unpack_slice::<ArrayString<8>>(&packed).unwrap(), s);
+}
+
+#[test]
+fn inline_vec_overflow() {
+    let packed = pack_vec(&vec![1u8, 2, 3]).unwrap();
+    assert!(matches!(
+        unpack_slice::<ArrayVec<u8, 2>>(&packed),
+        Err(UnpackErr::BufferOverflow)
+    ));
+    assert!(matches!(
+        unpack_slice::<tinyvec::ArrayVec<[u8; 2]>>(&packed),
+        Err(UnpackErr::BufferOverflow)
+    ));
+
+    let packed = pack_vec(&"hello").unwrap();
+    assert!(matches!(
+        unpack_slice::<ArrayString<4>>(&packed),
+        Err(UnpackErr::BufferOverflow)
+    ));
+}
@@ -0,0 +1,168 @@
+use std::cell::Cell;
+
+use msgpck::{pack_vec, unpack_slice, MsgPack, MsgUnpack};
+
+/// The first version of a message.
+#[derive(Debug, PartialEq, MsgPack, MsgUnpack)]
+pub struct StatusV1 {
+    #[msgpck(key = 1)]
+    pub id: u32,
+    #[msgpck(key = 2)]
+    pub name: String,
+}
+
+/// The second version of the message, where a field has been added.
+#[derive(Debug, PartialEq, MsgPack, MsgUnpack)]
+pub struct StatusV2 {
+    #[msgpck(key = 2)]
+    pub name: String,
+    #[msgpck(key = 1)]
+    pub id: u32,
+    #[msgpck(key = 3)]
+    pub errors: Vec<Error>,
+}
+
+#[derive(Debug, PartialEq, MsgPack, MsgUnpack)]
+pub struct Error {
+    pub code: u8,
+    pub message: String,
+}
+
+#[derive(Debug, PartialEq, MsgPack, MsgUnpack)]
+pub struct Sample(
+    #[msgpck(key = 0)] i16,
+    #[msgpck(skip)] Option<u8>,
+    #[msgpck(key = 200)] bool,
+);
+
+#[derive(Debug, Default, PartialEq, MsgPack, MsgUnpack)]
+pub struct Config {
+    #[msgpck(key = 0, skip_if = "Option::is_none")]
+    pub timeout: Option<u16>,
+    #[msgpck(key = 1)]
+    pub retries: u8,
+    #[msgpck(key = 2, skip_if = "Vec::is_empty")]
+    pub peers: Vec<String>,
+}
+
+thread_local! {
+    static SKIP_IF_CALLS: Cell<usize> = const { Cell::new(0) };
+}
+
+/// A `skip_if` predicate that counts how often it's called.
+fn counted_is_zero(value: &u8) -> bool {
+    SKIP_IF_CALLS.with(|calls| calls.set(calls.get() + 1));
+    *value == 0
+}
+
+#[derive(Debug, PartialEq, MsgPack)]
+pub struct Counted {
+    #[msgpck(key = 0, skip_if = "counted_is_zero")]
+    pub value: u8,
+}
+
+/// Pack using [MsgPack::pack] instead of [MsgPack::pack_with_writer].
+fn pack_with_iterator(v: &impl MsgPack) -> Vec<u8> {
+    v.pack().flat_map(|p| p.as_bytes().to_vec()).collect()
+}
+
+#[test]
+fn keyed_struct() {
+    let status = StatusV1 {
+        id: 7,
+        name: "node".into(),
+    };
+    let packed = pack_vec(&status).unwrap();
+    assert_eq!(
+        packed,
+        [0x82, 0x01, 0x07, 0x02, 0xa4, b'n', b'o', b'd', b'e']
+    );
+    assert_eq!(packed, pack_with_iterator(&status));
+    assert_eq!(unpack_slice::<StatusV1>(&packed).unwrap(), status);
+
+    let sample = Sample(-300, None, true);
+    let packed = pack_vec(&sample).unwrap();
+    assert_eq!(packed, [0x82, 0x00, 0xd1, 0xfe, 0xd4, 0xcc, 0xc8, 0xc3]);
+    assert_eq!(packed, pack_with_iterator(&sample));
+    assert_eq!(unpack_slice::<Sample>(&packed).unwrap(), sample);
+}
+
+#[test]
+fn keyed_schema_evolution() {
+    let v1 = StatusV1 {
+        id: 7,
+        name: "node".into(),
+    };
+    let v2 = StatusV2 {
+        name: "node".into(),
+        id: 7,
+        errors: vec![
+            Error {
+                code: 1,
+                message: "timeout".into(),
+            },
+            Error {
+                code: 4,
+                message: "overheated".into(),
+            },
+        ],
+    };
+
+    // missing fields are defaulted
+    let packed = pack_vec(&v1).unwrap();
+    let unpacked: StatusV2 = unpack_slice(&packed).unwrap();
+    assert_eq!(
+        unpacked,
+        StatusV2 {
+            name: "node".into(),
+            id: 7,
+            errors: vec![],
+        }
+    );
+
+    // unknown fields are skipped, and the order of the fields doesn't matter
+    let packed = pack_vec(&v2).unwrap();
+    assert_eq!(unpack_slice::<StatusV1>(&packed).unwrap(), v1);
+}
+
+#[test]
+fn keyed_skip_if() {
+    let config = Config {
+        timeout: None,
+        retries: 3,
+        peers: vec![],
+    };
+    let packed = pack_vec(&config).unwrap();
+    assert_eq!(packed, [0x81, 0x01, 0x03]);
+    assert_eq!(packed, pack_with_iterator(&config));
+    assert_eq!(unpack_slice::<Config>(&packed).unwrap(), config);
+
+    let config = Config {
+        timeout: Some(500),
+        retries: 0,
+        peers: vec!["a".into()],
+    };
+    let packed = pack_vec(&config).unwrap();
+    assert_eq!(
+        packed,
+        [0x83, 0x00, 0xcd, 0x01, 0xf4, 0x01, 0x00, 0x02, 0x91, 0xa1, b'a']
+    );
+    assert_eq!(packed, pack_with_iterator(&config));
+    assert_eq!(unpack_slice::<Config>(&packed).unwrap(), config);
+
+    let packed = pack_vec(&Config::default()).unwrap();
+    assert_eq!(packed, [0x81, 0x01, 0x00]);
+}
+
+#[test]
+fn skip_if_called_once() {
+    for value in [0, 1] {
+        SKIP_IF_CALLS.with(|calls| calls.set(0));
+        pack_vec(&Counted { value }).unwrap();
+        assert_eq!(SKIP_IF_CALLS.with(Cell::get), 1);
+
+        SKIP_IF_CALLS.with(|calls| calls.set(0));
+        pack_with_iterator(&Counted { value });
+        assert_eq!(SKIP_IF_CALLS.with(Cell::get), 1);
+    }
+}
@@ -0,0 +1,141 @@
+use msgpck::{unpack_slice, unpack_slice_canonical, unpack_slice_strict, UnpackErr};
+
+/// 7 packed as an i16.
+const SEVEN_I16: [u8; 3] = [0xd1, 0x00, 0x07];
+
+/// 0.5 packed as an f64.
+const HALF_F64: [u8; 9] = [0xcb, 0x3f, 0xe0, 0, 0, 0, 0, 0, 0];
+
+#[test]
+fn lenient_ints() {
+    assert_eq!(unpack_slice::<u8>(&SEVEN_I16).unwrap(), 7);
+    assert_eq!(
+        unpack_slice::<u64>(&[0xd3, 0, 0, 0, 0, 0, 0, 1, 0]).unwrap(),
+        256
+    );
+
+    // negative values still can't be unpacked as unsigned integers
+    let err = unpack_slice::<u32>(&[0xff]).unwrap_err();
+    assert!(matches!(err, UnpackErr::IntTooBig(_)));
+    let err = unpack_slice::<u16>(&[0xd0, 0x80]).unwrap_err();
+    assert!(matches!(err, UnpackErr::IntTooBig(_)));
+}
+
+#[test]
+fn lenient_floats() {
+    use msgpck::pack_vec;
+
+    assert_eq!(unpack_slice::<f32>(&HALF_F64).unwrap(), 0.5);
+    assert_eq!(unpack_slice::<f64>(&[0xca, 0x3f, 0, 0, 0]).unwrap(), 0.5);
+    assert!(unpack_slice::<f32>(&[0xcb, 0x7f, 0xf8, 0, 0, 0, 0, 0, 0])
+        .unwrap()
+        .is_nan());
+
+    assert_eq!(unpack_slice::<f32>(&SEVEN_I16).unwrap(), 7.0);
+    assert_eq!(unpack_slice::<f64>(&[0xe0]).unwrap(), -32.0);
+    assert_eq!(
+        unpack_slice::<f64>(&[0xcf, 0, 0x20, 0, 0, 0, 0, 0, 0]).unwrap(),
+        2f64.powi(53)
+    );
+
+    // values that can't be represented exactly aren't rounded
+    let err = unpack_slice::<f32>(&pack_vec(&0.1f64).unwrap()).unwrap_err();
+    assert!(matches!(err, UnpackErr::LossyFloat));
+    let err = unpack_slice::<f32>(&pack_vec(&16_777_217u32).unwrap()).unwrap_err();
+    assert!(matches!(err, UnpackErr::LossyFloat));
+    let err = unpack_slice::<f64>(&pack_vec(&i64::MAX).unwrap()).unwrap_err();
+    assert!(matches!(err, UnpackErr::LossyFloat));
+}
+
+#[test]
+fn strict_numbers() {
+    let err = unpack_slice_strict::<u8>(&SEVEN_I16).unwrap_err();
+    assert!(matches!(err, UnpackErr::WrongMarker { .. }));
+    let err = unpack_slice_strict::<f32>(&HALF_F64).unwrap_err();
+    assert!(matches!(err, UnpackErr::WrongMarker { .. }));
+    let err = unpack_slice_strict::<f64>(&SEVEN_I16).unwrap_err();
+    assert!(matches!(err, UnpackErr::WrongMarker { .. }));
+
+    assert_eq!(unpack_slice_strict::<i8>(&[0x07]).unwrap(), 7);
+    assert_eq!(unpack_slice_strict::<f64>(&HALF_F64).unwrap(), 0.5);
+}
+
+#[test]
+fn strict_nested_numbers() {
+    #[derive(Debug, msgpck::MsgUnpack)]
+    struct Reading {
+        values: Vec<(u8, Option<f32>)>,
+    }
+
+    // [[[1, 7 as an i16]]]
+    let packed = [0x91, 0x91, 0x92, 0x01, 0xd1, 0x00, 0x07];
+    assert_eq!(
+        unpack_slice::<Reading>(&packed).unwrap().values,
+        [(1, Some(7.0))]
+    );
+    let err = unpack_slice_strict::<Reading>(&packed).unwrap_err();
+    assert!(matches!(err, UnpackErr::WrongMarker { .. }));
+}
+
+#[test]
+fn int128_from_ints() {
+    use msgpck::pack_vec;
+
+    assert_eq!(unpack_slice::<u128>(&[0x07]).unwrap(), 7);
+    assert_eq!(unpack_slice::<i128>(&[0xe0]).unwrap(), -32);
+    assert_eq!(
+        unpack_slice::<i128>(&pack_vec(&u64::MAX).unwrap()).unwrap(),
+        u64::MAX.into()
+    );
+    let err = unpack_slice::<u128>(&[0xe0]).unwrap_err();
+    assert!(!matches!(err, UnpackErr::NonCanonical));
+
+    let err = unpack_slice_canonical::<u128>(&[0x07]).unwrap_err();
+    assert!(matches!(err, UnpackErr::NonCanonical));
+
+    // a bin of any other length than 16 isn't a 128-bit integer
+    let err = unpack_slice::<u128>(&pack_vec(&msgpck::Bytes(&[1, 2, 3])).unwrap()).unwrap_err();
+    assert!(matches!(err, UnpackErr::InvalidInt128));
+
+    let err = unpack_slice::<i128>(&[0xc0]).unwrap_err();
+    assert!(
+        matches!(err, UnpackErr::WrongMarker { expected, .. } if expected.contains(msgpck::ExpectedKind::BIN))
+    );
+}
+
+#[test]
+fn non_zero_rejects_zero() {
+    use std::num::{NonZeroU128, NonZeroU8};
+
+    assert_eq!(unpack_slice::<NonZeroU8>(&[0x07]).unwrap().get(), 7);
+
+    let err = unpack_slice::<NonZeroU8>(&[0x00]).unwrap_err();
+    assert!(matches!(err, UnpackErr::UnexpectedZero));
+
+    let zero = msgpck::pack_vec(&0u128).unwrap();
+    let err = unpack_slice::<NonZeroU128>(&zero).unwrap_err();
+    assert!(matches!(err, UnpackErr::UnexpectedZero));
+}
+
+#[test]
+fn chars_are_single_character_strings() {
+    use msgpck::pack_vec;
+    use std::num::Saturating;
+
+    assert_eq!(pack_vec(&'é').unwrap(), pack_vec(&"é").unwrap());
+    assert_eq!(
+        unpack_slice::<char>(&pack_vec(&"🦀").unwrap()).unwrap(),
+        '🦀'
+    );
+
+    for s in ["", "ab"] {
+        let err = unpack_slice::<char>(&pack_vec(&s).unwrap()).unwrap_err();
+        assert!(matches!(err, UnpackErr::InvalidChar));
+    }
+
+    let packed = pack_vec(&Saturating(200u8)).unwrap();
+    assert_eq!(
+        unpack_slice::<Saturating<u8>>(&packed).unwrap(),
+        Saturating(200)
+    );
+}
@@ -0,0 +1,46 @@
+use indexmap::{IndexMap, IndexSet};
+use msgpck::{pack_vec, unpack_slice, MapVec};
+
+/// `{"b": 2, "a": 1, "c": 3}`
+const PACKED: [u8; 10] = [0x83, 0xa1, b'b', 0x02, 0xa1, b'a', 0x01, 0xa1, b'c', 0x03];
+
+#[test]
+fn index_map_preserves_order() {
+    let map: IndexMap<String, u8> = unpack_slice(&PACKED).unwrap();
+    assert!(map.keys().eq(["b", "a", "c"]));
+    assert_eq!(pack_vec(&map).unwrap(), PACKED);
+
+    let set: IndexSet<u16> = [300, 1, 2].into_iter().collect();
+    let packed = pack_vec(&set).unwrap();
+    assert_eq!(packed, pack_vec(&[300u16, 1, 2]).unwrap());
+    let unpacked: IndexSet<u16> = unpack_slice(&packed).unwrap();
+    assert!(unpacked.iter().eq(set.iter()));
+}
+
+#[test]
+fn map_vec_preserves_order() {
+    let map: MapVec<&str, u8> = unpack_slice(&PACKED).unwrap();
+    assert_eq!(map.0, [("b", 2), ("a", 1), ("c", 3)]);
+    assert_eq!(pack_vec(&map).unwrap(), PACKED);
+
+    let map: MapVec<String, Vec<u8>> = [("x".into(), vec![1]), ("y".into(), vec![])]
+        .into_iter()
+        .collect();
+    let packed = pack_vec(&map).unwrap();
+    assert_eq!(
+        unpack_slice::<MapVec<String, Vec<u8>>>(&packed).unwrap(),
+        map
+    );
+}
+
+#[test]
+fn map_vec_duplicate_keys() {
+    // `{"a": 1, "a": 2}`
+    let packed = [0x82, 0xa1, b'a', 0x01, 0xa1, b'a', 0x02];
+    let map: MapVec<&str, u8> = unpack_slice(&packed).unwrap();
+    assert_eq!(map.0, [("a", 1), ("a", 2)]);
+    assert_eq!(pack_vec(&map).unwrap(), packed);
+
+    let err = msgpck::unpack_slice_canonical::<MapVec<&str, u8>>(&packed).unwrap_err();
+    assert!(matches!(err, msgpck::UnpackErr::NonCanonical));
+}
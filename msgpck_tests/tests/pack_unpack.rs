@@ -1,6 +1,15 @@
 use msgpck_tests::*;
 use quickcheck_macros::quickcheck;
-use std::collections::HashMap;
+use std::collections::{
+    hash_map::DefaultHasher, BTreeMap, BTreeSet, HashMap, HashSet, LinkedList, VecDeque,
+};
+use std::hash::BuildHasherDefault;
+use std::marker::PhantomData;
+use std::net::{IpAddr, SocketAddr, SocketAddrV6};
+use std::num::{NonZeroU32, NonZeroU64, Wrapping};
+use std::ops::{Range, RangeFrom, RangeInclusive, RangeTo};
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 #[quickcheck]
 fn signed_integers(i: i64) {
@@ -99,3 +108,111 @@ fn struct_with_lifetime() {
     test_pack_unpack(&WithLifetime { s: "Hello there." });
 }
 */
+
+#[test]
+fn unit() {
+    test_pack_unpack(&());
+}
+
+#[quickcheck]
+fn tuples(v: (u32, String, Vec<u8>), w: (bool, Option<i16>, f32, u8, HashMap<u8, u8>)) {
+    if w.2.is_nan() {
+        return;
+    }
+    test_pack_unpack(&v);
+    test_pack_unpack(&w);
+    test_pack_unpack(&(v, w));
+}
+
+#[quickcheck]
+fn arrays(a: u32, b: String) {
+    test_pack_unpack(&[a, a / 2, a / 3, 0]);
+    test_pack_unpack(&[b.clone(), b]);
+    test_pack_unpack(&[0u8; 20]);
+    test_pack_unpack(&[(); 0]);
+}
+
+#[quickcheck]
+fn alloc_collections(
+    map: BTreeMap<u16, String>,
+    set: BTreeSet<i32>,
+    deque: VecDeque<u8>,
+    list: LinkedList<String>,
+) {
+    test_pack_unpack(&map);
+    test_pack_unpack(&set);
+    test_pack_unpack(&deque);
+    test_pack_unpack(&list);
+}
+
+#[quickcheck]
+fn boxed(s: String, v: Vec<u32>) {
+    test_pack_unpack(&s.into_boxed_str());
+    test_pack_unpack(&v.into_boxed_slice());
+}
+
+#[quickcheck]
+fn hash_collections(
+    set: HashSet<u32>,
+    map: HashMap<u8, String, BuildHasherDefault<DefaultHasher>>,
+) {
+    test_pack_unpack(&set);
+    test_pack_unpack(&map);
+}
+
+#[quickcheck]
+fn int128(u: u128, i: i128) {
+    test_pack_unpack(&u);
+    test_pack_unpack(&i);
+}
+
+#[quickcheck]
+fn chars(c: char) {
+    test_pack_unpack(&c);
+}
+
+#[quickcheck]
+fn non_zero(u: NonZeroU32, i: NonZeroU64, w: Wrapping<u16>) {
+    test_pack_unpack(&u);
+    test_pack_unpack(&i);
+    test_pack_unpack(&w);
+}
+
+#[quickcheck]
+fn times(d: Duration, t: SystemTime) {
+    test_pack_unpack(&d);
+    if t >= UNIX_EPOCH {
+        test_pack_unpack(&t);
+    }
+}
+
+#[quickcheck]
+fn net_addrs(ip: IpAddr, addr: SocketAddr) {
+    test_pack_unpack(&ip);
+
+    // the flow info and scope id of ipv6 socket addresses aren't packed
+    let addr = match addr {
+        SocketAddr::V6(a) => SocketAddr::V6(SocketAddrV6::new(*a.ip(), a.port(), 0, 0)),
+        a => a,
+    };
+    test_pack_unpack(&addr);
+}
+
+#[quickcheck]
+fn paths(p: PathBuf) {
+    test_pack_unpack(&p);
+}
+
+#[quickcheck]
+fn ranges(r: Range<u32>, i: RangeInclusive<i64>, f: RangeFrom<u8>, t: RangeTo<String>) {
+    test_pack_unpack(&r);
+    test_pack_unpack(&i);
+    test_pack_unpack(&f);
+    test_pack_unpack(&t);
+    test_pack_unpack(&PhantomData::<String>);
+}
+
+#[quickcheck]
+fn uuids(n: u128) {
+    test_pack_unpack(&uuid::Uuid::from_u128(n));
+}
@@ -0,0 +1,92 @@
+use std::{
+    ffi::OsStr,
+    net::{IpAddr, Ipv4Addr},
+    os::unix::ffi::OsStrExt,
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use msgpck::{pack_vec, unpack_slice, Timestamp, UnpackErr};
+
+#[test]
+fn system_time_before_epoch() {
+    // times before the epoch are packed as timestamps
+    let t = UNIX_EPOCH - Duration::new(1, 250_000_000);
+    let packed = pack_vec(&t).unwrap();
+    assert_eq!(
+        unpack_slice::<Timestamp>(&packed).unwrap(),
+        Timestamp::new(-2, 750_000_000).unwrap()
+    );
+    assert_eq!(unpack_slice::<SystemTime>(&packed).unwrap(), t);
+
+    let t = UNIX_EPOCH - Duration::from_secs(1);
+    let packed = pack_vec(&t).unwrap();
+    assert_eq!(packed, pack_vec(&Timestamp::new(-1, 0).unwrap()).unwrap());
+    assert_eq!(unpack_slice::<SystemTime>(&packed).unwrap(), t);
+
+    // later times may also be unpacked from timestamps
+    let packed = pack_vec(&Timestamp::new(1, 5).unwrap()).unwrap();
+    assert_eq!(
+        unpack_slice::<SystemTime>(&packed).unwrap(),
+        UNIX_EPOCH + Duration::new(1, 5)
+    );
+}
+
+#[test]
+fn duration_overflow() {
+    // [u64::MAX, 1_000_000_000]
+    let packed = [
+        0x92, 0xcf, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xce, 0x3b, 0x9a, 0xca, 0x00,
+    ];
+    assert!(matches!(
+        unpack_slice::<Duration>(&packed),
+        Err(UnpackErr::Other(_))
+    ));
+    assert!(unpack_slice::<SystemTime>(&packed).is_err());
+
+    // nanoseconds are carried over into seconds
+    let packed = [0x92, 0x01, 0xce, 0x3b, 0x9a, 0xca, 0x01];
+    assert_eq!(
+        unpack_slice::<Duration>(&packed).unwrap(),
+        Duration::new(2, 1)
+    );
+}
+
+#[test]
+fn ip_addr_variant_index() {
+    // {0: [127, 0, 0, 1]}
+    let packed = [0x81, 0x00, 0x94, 0x7f, 0x00, 0x00, 0x01];
+    assert_eq!(
+        unpack_slice::<IpAddr>(&packed).unwrap(),
+        IpAddr::V4(Ipv4Addr::LOCALHOST)
+    );
+
+    // {"V5": [127, 0, 0, 1]}
+    let packed = [0x81, 0xa2, b'V', b'5', 0x94, 0x7f, 0x00, 0x00, 0x01];
+    assert!(matches!(
+        unpack_slice::<IpAddr>(&packed),
+        Err(UnpackErr::UnknownVariant)
+    ));
+}
+
+#[test]
+fn paths() {
+    let packed = pack_vec(&PathBuf::from("/tmp/msgpck")).unwrap();
+    assert_eq!(
+        unpack_slice::<&Path>(&packed).unwrap(),
+        Path::new("/tmp/msgpck")
+    );
+
+    // paths that aren't valid UTF-8 are packed as bin
+    let invalid = Path::new(OsStr::from_bytes(b"/tmp/\xff"));
+    let packed = pack_vec(&invalid).unwrap();
+    assert_eq!(packed, [0xc4, 0x06, b'/', b't', b'm', b'p', b'/', 0xff]);
+    assert_eq!(unpack_slice::<&Path>(&packed).unwrap(), invalid);
+    assert_eq!(unpack_slice::<PathBuf>(&packed).unwrap(), invalid);
+
+    let err = unpack_slice::<PathBuf>(&[0xc3]).unwrap_err();
+    assert!(matches!(
+        err,
+        UnpackErr::WrongMarker { expected, .. } if expected == msgpck::ExpectedKind::STR | msgpck::ExpectedKind::BIN
+    ));
+}
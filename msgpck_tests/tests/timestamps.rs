@@ -0,0 +1,216 @@
+use chrono::{DateTime, NaiveDateTime, Utc};
+use msgpck::{pack_vec, unpack_slice, Timestamp, UnpackErr};
+use quickcheck_macros::quickcheck;
+
+/// 2023-11-14T22:13:20Z as a timestamp 32.
+const TIMESTAMP_32: [u8; 6] = [0xd6, 0xff, 0x65, 0x53, 0xf1, 0x00];
+
+/// 2023-11-14T22:13:20.5Z as a timestamp 64.
+const TIMESTAMP_64: [u8; 10] = [0xd7, 0xff, 0x77, 0x35, 0x94, 0x00, 0x65, 0x53, 0xf1, 0x00];
+
+/// 1969-12-31T23:59:59Z as a timestamp 96.
+const TIMESTAMP_96: [u8; 15] = [
+    0xc7, 0x0c, 0xff, 0x00, 0x00, 0x00, 0x00, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+];
+
+#[test]
+fn timestamp_formats() {
+    let cases = [
+        (Timestamp::new(1_700_000_000, 0).unwrap(), &TIMESTAMP_32[..]),
+        (
+            Timestamp::new(1_700_000_000, 500_000_000).unwrap(),
+            &TIMESTAMP_64[..],
+        ),
+        (Timestamp::new(-1, 0).unwrap(), &TIMESTAMP_96[..]),
+    ];
+
+    for (ts, packed) in cases {
+        assert_eq!(pack_vec(&ts).unwrap(), packed);
+        assert_eq!(unpack_slice::<Timestamp>(packed).unwrap(), ts);
+    }
+
+    assert!(Timestamp::new(0, 1_000_000_000).is_none());
+}
+
+#[quickcheck]
+fn timestamps_round_trip(seconds: i64, nanoseconds: u32) {
+    let ts = Timestamp::new(seconds, nanoseconds % 1_000_000_000).unwrap();
+    let packed = pack_vec(&ts).unwrap();
+    assert_eq!(unpack_slice::<Timestamp>(&packed).unwrap(), ts);
+}
+
+#[test]
+fn invalid_timestamps() {
+    // ext type 1 instead of -1
+    assert!(matches!(
+        unpack_slice::<Timestamp>(&[0xd6, 0x01, 0, 0, 0, 0]),
+        Err(UnpackErr::InvalidTimestamp)
+    ));
+
+    // 2 bytes of data
+    assert!(matches!(
+        unpack_slice::<Timestamp>(&[0xd5, 0xff, 0, 0]),
+        Err(UnpackErr::InvalidTimestamp)
+    ));
+
+    // 1,000,000,000 nanoseconds
+    let mut packed = vec![0xc7, 0x0c, 0xff];
+    packed.extend_from_slice(&1_000_000_000u32.to_be_bytes());
+    packed.extend_from_slice(&0i64.to_be_bytes());
+    assert!(matches!(
+        unpack_slice::<Timestamp>(&packed),
+        Err(UnpackErr::InvalidTimestamp)
+    ));
+
+    assert!(matches!(
+        unpack_slice::<Timestamp>(&[0xa0]),
+        Err(UnpackErr::WrongMarker { .. })
+    ));
+}
+
+#[test]
+fn chrono_date_times() {
+    let dt: DateTime<Utc> = "2023-11-14T22:13:20.5Z".parse().unwrap();
+    assert_eq!(pack_vec(&dt).unwrap(), TIMESTAMP_64);
+    assert_eq!(unpack_slice::<DateTime<Utc>>(&TIMESTAMP_64).unwrap(), dt);
+
+    let naive: NaiveDateTime = dt.naive_utc();
+    assert_eq!(pack_vec(&naive).unwrap(), TIMESTAMP_64);
+    assert_eq!(unpack_slice::<NaiveDateTime>(&TIMESTAMP_64).unwrap(), naive);
+
+    let before_epoch = DateTime::from_timestamp(-1, 0).unwrap();
+    assert_eq!(pack_vec(&before_epoch).unwrap(), TIMESTAMP_96);
+    assert_eq!(
+        unpack_slice::<DateTime<Utc>>(&TIMESTAMP_96).unwrap(),
+        before_epoch
+    );
+}
+
+#[test]
+fn chrono_leap_second() {
+    // 2016-12-31T23:59:60.5Z is packed as the next second
+    let leap = DateTime::from_timestamp(1_483_228_799, 1_500_000_000).unwrap();
+    let packed = pack_vec(&leap).unwrap();
+    assert_eq!(
+        unpack_slice::<Timestamp>(&packed).unwrap(),
+        Timestamp::new(1_483_228_800, 500_000_000).unwrap()
+    );
+}
+
+#[test]
+fn time_date_times() {
+    let dt = time::OffsetDateTime::from_unix_timestamp_nanos(1_700_000_000_500_000_000).unwrap();
+    assert_eq!(pack_vec(&dt).unwrap(), TIMESTAMP_64);
+    assert_eq!(
+        unpack_slice::<time::OffsetDateTime>(&TIMESTAMP_64).unwrap(),
+        dt
+    );
+
+    let primitive = time::PrimitiveDateTime::new(dt.date(), dt.time());
+    assert_eq!(pack_vec(&primitive).unwrap(), TIMESTAMP_64);
+    assert_eq!(
+        unpack_slice::<time::PrimitiveDateTime>(&TIMESTAMP_64).unwrap(),
+        primitive
+    );
+
+    // the offset isn't packed
+    let offset = dt.to_offset(time::UtcOffset::from_hms(2, 0, 0).unwrap());
+    assert_eq!(pack_vec(&offset).unwrap(), TIMESTAMP_64);
+}
+
+#[test]
+fn jiff_timestamps() {
+    let ts = jiff::Timestamp::new(1_700_000_000, 500_000_000).unwrap();
+    assert_eq!(pack_vec(&ts).unwrap(), TIMESTAMP_64);
+    assert_eq!(unpack_slice::<jiff::Timestamp>(&TIMESTAMP_64).unwrap(), ts);
+
+    // jiff has negative nanoseconds before the epoch
+    let ts = jiff::Timestamp::new(0, -500_000_000).unwrap();
+    assert_eq!(
+        unpack_slice::<Timestamp>(&pack_vec(&ts).unwrap()).unwrap(),
+        Timestamp::new(-1, 500_000_000).unwrap()
+    );
+    assert_eq!(
+        unpack_slice::<jiff::Timestamp>(&pack_vec(&ts).unwrap()).unwrap(),
+        ts
+    );
+}
+
+#[test]
+fn from_rfc3339_strings() {
+    let packed = pack_vec(&"2023-11-14T23:13:20.5+01:00").unwrap();
+    let expected = Timestamp::new(1_700_000_000, 500_000_000).unwrap();
+
+    let dt = unpack_slice::<DateTime<Utc>>(&packed).unwrap();
+    assert_eq!(dt.timestamp(), expected.seconds());
+    assert_eq!(dt.timestamp_subsec_nanos(), expected.nanoseconds());
+
+    let dt = unpack_slice::<time::OffsetDateTime>(&packed).unwrap();
+    assert_eq!(dt.unix_timestamp(), expected.seconds());
+    assert_eq!(dt.nanosecond(), expected.nanoseconds());
+
+    let ts = unpack_slice::<jiff::Timestamp>(&packed).unwrap();
+    assert_eq!(ts.as_second(), expected.seconds());
+    assert_eq!(ts.subsec_nanosecond() as u32, expected.nanoseconds());
+
+    let packed = pack_vec(&"yesterday").unwrap();
+    assert!(matches!(
+        unpack_slice::<DateTime<Utc>>(&packed),
+        Err(UnpackErr::Conversion(_))
+    ));
+    assert!(matches!(
+        unpack_slice::<time::OffsetDateTime>(&packed),
+        Err(UnpackErr::Conversion(_))
+    ));
+    assert!(matches!(
+        unpack_slice::<jiff::Timestamp>(&packed),
+        Err(UnpackErr::Conversion(_))
+    ));
+}
+
+#[test]
+fn from_epoch_seconds() {
+    let packed = pack_vec(&1_700_000_000u32).unwrap();
+    let expected = Timestamp::new(1_700_000_000, 0).unwrap();
+    assert_eq!(unpack_slice::<Timestamp>(&packed).unwrap(), expected);
+    assert_eq!(
+        unpack_slice::<DateTime<Utc>>(&packed).unwrap().timestamp(),
+        expected.seconds()
+    );
+    assert_eq!(
+        unpack_slice::<time::OffsetDateTime>(&packed)
+            .unwrap()
+            .unix_timestamp(),
+        expected.seconds()
+    );
+    assert_eq!(
+        unpack_slice::<jiff::Timestamp>(&packed)
+            .unwrap()
+            .as_second(),
+        expected.seconds()
+    );
+
+    let packed = pack_vec(&-1i8).unwrap();
+    assert_eq!(
+        unpack_slice::<Timestamp>(&packed).unwrap(),
+        Timestamp::new(-1, 0).unwrap()
+    );
+}
+
+#[test]
+fn out_of_range() {
+    let ts = Timestamp::new(i64::MAX, 0).unwrap();
+    let packed = pack_vec(&ts).unwrap();
+    assert!(matches!(
+        unpack_slice::<DateTime<Utc>>(&packed),
+        Err(UnpackErr::Other(_))
+    ));
+    assert!(matches!(
+        unpack_slice::<time::OffsetDateTime>(&packed),
+        Err(UnpackErr::Other(_))
+    ));
+    assert!(matches!(
+        unpack_slice::<jiff::Timestamp>(&packed),
+        Err(UnpackErr::Other(_))
+    ));
+}
@@ -0,0 +1,65 @@
+use msgpck::{pack_vec, unpack_slice, unpack_slice_with_context, UnpackErr};
+
+type U8x16 = (
+    u8,
+    u8,
+    u8,
+    u8,
+    u8,
+    u8,
+    u8,
+    u8,
+    u8,
+    u8,
+    u8,
+    u8,
+    u8,
+    u8,
+    u8,
+    u8,
+);
+
+#[test]
+fn tuple_arity_16() {
+    let tuple = (
+        0u8, 1u8, 2u8, 3u8, 4u8, 5u8, 6u8, 7u8, 8u8, 9u8, 10u8, 11u8, 12u8, 13u8, 14u8, 15u8,
+    );
+    let packed = pack_vec(&tuple).unwrap();
+
+    assert_eq!(packed[0], 0xdc, "16 elements don't fit in a fixarray");
+
+    // std doesn't implement PartialEq for tuples this large, so compare a few of the elements
+    let unpacked: U8x16 = unpack_slice(&packed).unwrap();
+    assert_eq!((unpacked.0, unpacked.7, unpacked.15), (0, 7, 15));
+}
+
+#[test]
+fn tuple_wrong_length() {
+    let packed = pack_vec(&(1u8, "two")).unwrap();
+
+    let err = unpack_slice::<(u8, &str, bool)>(&packed).unwrap_err();
+    assert!(matches!(
+        err,
+        UnpackErr::MissingFields {
+            got: 2,
+            expected: 3
+        }
+    ));
+
+    let err = unpack_slice::<(u8,)>(&packed).unwrap_err();
+    assert!(matches!(
+        err,
+        UnpackErr::TooManyFields {
+            got: 2,
+            expected: 1
+        }
+    ));
+}
+
+#[test]
+fn tuple_error_path() {
+    let packed = pack_vec(&(1u8, "two")).unwrap();
+
+    let err = unpack_slice_with_context::<(u8, u8)>(&packed).unwrap_err();
+    assert_eq!(err.path(), [msgpck::PathSegment::Index(1)]);
+}
@@ -0,0 +1,49 @@
+use msgpck::{pack_vec, unpack_slice, UnpackErr};
+use uuid::Uuid;
+
+const UUID: &str = "67e55044-10b1-426f-9247-bb680e5fe0c8";
+
+#[test]
+fn uuid_as_bin() {
+    let uuid = Uuid::parse_str(UUID).unwrap();
+    let packed = pack_vec(&uuid).unwrap();
+    assert_eq!(packed[..2], [0xc4, 0x10]);
+    assert_eq!(&packed[2..], uuid.as_bytes());
+    assert_eq!(unpack_slice::<Uuid>(&packed).unwrap(), uuid);
+
+    // uuid::serde::compact packs the bytes as an array
+    let packed = pack_vec(uuid.as_bytes()).unwrap();
+    assert_eq!(unpack_slice::<Uuid>(&packed).unwrap(), uuid);
+}
+
+#[test]
+fn uuid_from_str() {
+    let packed = pack_vec(&UUID).unwrap();
+    assert_eq!(
+        unpack_slice::<Uuid>(&packed).unwrap(),
+        Uuid::parse_str(UUID).unwrap()
+    );
+
+    let packed = pack_vec(&"67e55044-10b1").unwrap();
+    assert!(matches!(
+        unpack_slice::<Uuid>(&packed),
+        Err(UnpackErr::Conversion(_))
+    ));
+}
+
+#[test]
+fn uuid_wrong_length() {
+    let packed = pack_vec(&msgpck::Bytes(&[0; 15])).unwrap();
+    assert!(matches!(
+        unpack_slice::<Uuid>(&packed),
+        Err(UnpackErr::InvalidLength {
+            expected: 16,
+            found: 15
+        })
+    ));
+
+    assert!(matches!(
+        unpack_slice::<Uuid>(&[0x2a]),
+        Err(UnpackErr::WrongMarker { .. })
+    ));
+}
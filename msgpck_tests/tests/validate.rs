@@ -0,0 +1,92 @@
+use msgpck::{pack_vec, unpack_slice, MsgPack, MsgUnpack, UnpackErr};
+
+fn non_empty(s: &String) -> Result<(), &'static str> {
+    if s.is_empty() {
+        return Err("name must not be empty");
+    }
+    Ok(())
+}
+
+fn valid_range(range: &Range) -> Result<(), &'static str> {
+    if range.min > range.max {
+        return Err("min must not be larger than max");
+    }
+    Ok(())
+}
+
+#[derive(Debug, PartialEq, MsgPack, MsgUnpack)]
+#[msgpck(validate = "valid_range")]
+pub struct Range {
+    #[msgpck(validate = "non_empty")]
+    pub name: String,
+    pub min: i32,
+    pub max: i32,
+}
+
+#[derive(Debug, PartialEq, MsgPack, MsgUnpack)]
+#[msgpck(validate = "Command::validate")]
+pub enum Command {
+    Reset,
+    Set(#[msgpck(validate = "non_empty")] String, u8),
+}
+
+impl Command {
+    fn validate(&self) -> Result<(), &'static str> {
+        match self {
+            Command::Set(_, 0) => Err("value must not be zero"),
+            _ => Ok(()),
+        }
+    }
+}
+
+fn assert_invalid<T>(result: Result<T, UnpackErr>, expected: &str) {
+    match result {
+        Err(UnpackErr::Invalid { message }) => assert_eq!(message, expected),
+        Err(e) => panic!("expected an invalid value, got {e:?}"),
+        Ok(_) => panic!("expected an invalid value"),
+    }
+}
+
+#[test]
+fn validate_struct() {
+    let range = Range {
+        name: "temperature".into(),
+        min: -40,
+        max: 85,
+    };
+    let packed = pack_vec(&range).unwrap();
+    assert_eq!(unpack_slice::<Range>(&packed).unwrap(), range);
+
+    let packed = pack_vec(&Range {
+        name: "temperature".into(),
+        min: 85,
+        max: -40,
+    })
+    .unwrap();
+    assert_invalid(
+        unpack_slice::<Range>(&packed),
+        "min must not be larger than max",
+    );
+
+    let packed = pack_vec(&Range {
+        name: String::new(),
+        min: 85,
+        max: -40,
+    })
+    .unwrap();
+    assert_invalid(unpack_slice::<Range>(&packed), "name must not be empty");
+}
+
+#[test]
+fn validate_enum() {
+    for command in [Command::Reset, Command::Set("speed".into(), 3)] {
+        let packed = pack_vec(&command).unwrap();
+        assert_eq!(unpack_slice::<Command>(&packed).unwrap(), command);
+    }
+
+    let packed = pack_vec(&Command::Set("speed".into(), 0)).unwrap();
+    assert_invalid(unpack_slice::<Command>(&packed), "value must not be zero");
+
+    let packed = pack_vec(&Command::Set(String::new(), 0)).unwrap();
+    assert_invalid(unpack_slice::<Command>(&packed), "name must not be empty");
+}
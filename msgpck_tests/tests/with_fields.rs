@@ -0,0 +1,98 @@
+use msgpck::{pack_vec, unpack_slice, MsgPack, MsgUnpack, PackErr, Piece, UnpackErr, Write};
+
+/// A type that doesn't impl [MsgPack] or [MsgUnpack].
+#[derive(Debug, PartialEq)]
+pub struct Celsius(f32);
+
+/// Pack [Celsius] as an integer amount of millidegrees.
+mod millidegrees {
+    use super::*;
+
+    pub fn pack(value: &Celsius) -> impl Iterator<Item = Piece<'_>> {
+        msgpck::helpers::pack_i64((value.0 * 1000.0) as i64).pieces()
+    }
+
+    pub fn pack_with_writer(value: &Celsius, w: &mut dyn Write) -> Result<usize, PackErr> {
+        ((value.0 * 1000.0) as i64).pack_with_writer(w)
+    }
+
+    pub fn unpack(bytes: &mut &[u8]) -> Result<Celsius, UnpackErr> {
+        let millis = msgpck::helpers::unpack_i64(bytes, &msgpck::UnpackCtx::new())?;
+        Ok(Celsius(millis as f32 / 1000.0))
+    }
+}
+
+fn pack_name(name: &str) -> impl Iterator<Item = Piece<'_>> {
+    name.trim().pack()
+}
+
+fn unpack_name(bytes: &mut &[u8]) -> Result<String, UnpackErr> {
+    let name: &str = MsgUnpack::unpack(bytes)?;
+    Ok(name.to_uppercase())
+}
+
+#[derive(Debug, PartialEq, MsgPack, MsgUnpack)]
+pub struct Reading {
+    pub sensor: u8,
+    #[msgpck(with = "millidegrees")]
+    pub temperature: Celsius,
+    #[msgpck(pack_with = "pack_name", unpack_with = "unpack_name")]
+    pub name: String,
+}
+
+#[derive(Debug, PartialEq, MsgPack, MsgUnpack)]
+pub enum Event {
+    Reading(#[msgpck(with = "millidegrees")] Celsius),
+    Alarm {
+        sensor: u8,
+        #[msgpck(with = "millidegrees")]
+        threshold: Celsius,
+    },
+}
+
+#[test]
+fn pack_unpack_struct_with() {
+    let reading = Reading {
+        sensor: 3,
+        temperature: Celsius(-1.5),
+        name: " outdoor ".into(),
+    };
+
+    let packed = pack_vec(&reading).unwrap();
+    let packed_iter: Vec<u8> = reading.pack().flat_map(|p| p.as_bytes().to_vec()).collect();
+    assert_eq!(packed, packed_iter);
+
+    // [3, -1500, "outdoor"]
+    let mut expected = vec![0x93, 0x03, 0xd1];
+    expected.extend_from_slice(&(-1500i16).to_be_bytes());
+    expected.push(0xa7);
+    expected.extend_from_slice(b"outdoor");
+    assert_eq!(packed, expected);
+
+    let unpacked: Reading = unpack_slice(&packed).unwrap();
+    assert_eq!(
+        unpacked,
+        Reading {
+            sensor: 3,
+            temperature: Celsius(-1.5),
+            name: "OUTDOOR".into(),
+        }
+    );
+}
+
+#[test]
+fn pack_unpack_enum_with() {
+    let events = [
+        Event::Reading(Celsius(21.0)),
+        Event::Alarm {
+            sensor: 1,
+            threshold: Celsius(80.0),
+        },
+    ];
+
+    for event in events {
+        let packed = pack_vec(&event).unwrap();
+        let unpacked: Event = unpack_slice(&packed).unwrap();
+        assert_eq!(unpacked, event);
+    }
+}
@@ -0,0 +1,51 @@
+use msgpck::{unpack_slice, unpack_slice_strict, ExpectedKind, Marker, MsgUnpack, UnpackErr};
+
+/// Unpack a `T`, and return the found marker and the expected kinds of the error.
+fn wrong_marker<'a, T: MsgUnpack<'a>>(bytes: &'a [u8]) -> (Marker, ExpectedKind) {
+    match unpack_slice::<T>(bytes).map(|_| ()).unwrap_err() {
+        UnpackErr::WrongMarker { found, expected } => (found, expected),
+        e => panic!("expected a wrong marker error, got {e:?}"),
+    }
+}
+
+#[test]
+fn expected_kinds() {
+    let fixstr = [0xa1, b'a'];
+    assert_eq!(
+        wrong_marker::<u8>(&fixstr),
+        (Marker::FixStr(1), ExpectedKind::UINT | ExpectedKind::INT)
+    );
+    assert!(matches!(
+        unpack_slice_strict::<u8>(&fixstr),
+        Err(UnpackErr::WrongMarker {
+            found: Marker::FixStr(1),
+            expected: ExpectedKind::UINT,
+        })
+    ));
+    assert_eq!(
+        wrong_marker::<i32>(&fixstr),
+        (Marker::FixStr(1), ExpectedKind::UINT | ExpectedKind::INT)
+    );
+    assert_eq!(
+        wrong_marker::<Option<bool>>(&fixstr),
+        (Marker::FixStr(1), ExpectedKind::BOOL | ExpectedKind::NIL)
+    );
+    assert_eq!(
+        wrong_marker::<Vec<u8>>(&fixstr),
+        (Marker::FixStr(1), ExpectedKind::ARRAY)
+    );
+    assert_eq!(
+        wrong_marker::<&str>(&[0xc3]),
+        (Marker::True, ExpectedKind::STR)
+    );
+}
+
+#[test]
+fn expected_kinds_display() {
+    let err = unpack_slice::<Option<i8>>(&[0x90]).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Wrong marker, expected nil or uint or int, got FixArray(0)"
+    );
+    assert_eq!(ExpectedKind::ANY.to_string(), "any value");
+}